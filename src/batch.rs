@@ -0,0 +1,126 @@
+use std::collections::HashMap;
+use std::hash::BuildHasher;
+
+use bumpalo::Bump;
+use hashbrown::DefaultHashBuilder;
+use serde_json::value::RawValue;
+
+use crate::interner::Interner;
+use crate::map::FrozenRawMap;
+use crate::value::Kind;
+use crate::Error;
+
+/// A batch of documents deduplicated by [`dedup_by_primary_key`].
+#[derive(Debug)]
+pub struct DedupBatch<'a, 'doc, S> {
+    /// The last occurrence of each document, in their original relative order.
+    pub documents: Vec<FrozenRawMap<'a, 'doc, S>>,
+    /// The documents that were dropped because a later document has the same primary key,
+    /// in their original order.
+    pub duplicates: Vec<Duplicate>,
+}
+
+/// A document dropped from a batch by [`dedup_by_primary_key`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Duplicate {
+    /// The primary key of the document, as a string.
+    pub id: String,
+    /// The index of the dropped document in the batch.
+    pub index: usize,
+    /// The index in the batch of the document that replaces it.
+    pub replaced_by: usize,
+}
+
+/// Removes the documents of a batch that have the same primary key as a later document.
+///
+/// The primary key is a dotted path to a string or an integer, and may point inside nested objects.
+/// An integer and a string holding its decimal representation are the same primary key,
+/// so that `{"id": 1}` and `{"id": "1"}` are duplicates.
+///
+/// # Errors
+///
+/// - if a document has no primary key, or if it is not a string nor an integer.
+pub fn dedup_by_primary_key<'a, 'doc, S, I>(
+    documents: I,
+    primary_key: &str,
+) -> Result<DedupBatch<'a, 'doc, S>, Error>
+where
+    S: BuildHasher,
+    I: IntoIterator<Item = FrozenRawMap<'a, 'doc, S>>,
+{
+    let mut ids = Vec::new();
+    let mut last_occurrences = HashMap::new();
+    let documents: Vec<_> = documents.into_iter().collect();
+    for (index, document) in documents.iter().enumerate() {
+        let invalid = |value: Option<&RawValue>| Error::InvalidPrimaryKey {
+            primary_key: primary_key.to_owned(),
+            index,
+            value: value.map(|value| value.get().to_owned()),
+        };
+        let value = document
+            .deserialize_at::<&RawValue>(primary_key)
+            .map_err(|_| invalid(None))?
+            .ok_or_else(|| invalid(None))?;
+        let id = document_id(value).ok_or_else(|| invalid(Some(value)))?;
+        last_occurrences.insert(id.clone(), index);
+        ids.push(id);
+    }
+
+    let mut batch = DedupBatch {
+        documents: Vec::with_capacity(last_occurrences.len()),
+        duplicates: Vec::new(),
+    };
+    for (index, (document, id)) in documents.into_iter().zip(ids).enumerate() {
+        let replaced_by = last_occurrences[&id];
+        if replaced_by == index {
+            batch.documents.push(document);
+        } else {
+            batch.duplicates.push(Duplicate {
+                id,
+                index,
+                replaced_by,
+            });
+        }
+    }
+    Ok(batch)
+}
+
+/// Counts the number of documents of a batch in which each top-level key appears.
+///
+/// The keys are interned in `interner`, and the counts are allocated in its bump,
+/// so that the keys shared by the documents are copied once per batch.
+pub fn key_frequencies<'a, 'doc, 'm, 'bump, S, I>(
+    documents: I,
+    interner: &mut Interner<'bump>,
+) -> hashbrown::HashMap<&'bump str, u64, DefaultHashBuilder, &'bump Bump>
+where
+    'doc: 'a,
+    'a: 'm,
+    S: 'a,
+    I: IntoIterator<Item = &'m FrozenRawMap<'a, 'doc, S>>,
+{
+    let bump = *interner.as_set().allocator();
+    let mut frequencies = hashbrown::HashMap::new_in(bump);
+    for document in documents {
+        for (key, _) in document.iter() {
+            *frequencies.entry(interner.interned(key)).or_insert(0) += 1;
+        }
+    }
+    frequencies
+}
+
+/// Returns the string form of a primary key, if it is a string or an integer.
+fn document_id(value: &RawValue) -> Option<String> {
+    match Kind::of(value) {
+        Kind::String => serde_json::from_str(value.get()).ok(),
+        Kind::Number => {
+            let number = value.get();
+            number
+                .parse::<i64>()
+                .map(|number| number.to_string())
+                .or_else(|_| number.parse::<u64>().map(|number| number.to_string()))
+                .ok()
+        }
+        _ => None,
+    }
+}
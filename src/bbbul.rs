@@ -6,6 +6,8 @@ use std::ptr::{self, NonNull};
 
 use bumpalo::Bump;
 
+use crate::Freeze;
+
 pub use bitpacking::{BitPacker, BitPacker1x, BitPacker4x, BitPacker8x};
 
 /// A Bit-packed Bumpalo-Based Unsigned integers Linked-list is a list
@@ -36,6 +38,7 @@ pub use bitpacking::{BitPacker, BitPacker1x, BitPacker4x, BitPacker8x};
 pub struct Bbbul<'bump, B> {
     bump: &'bump Bump,
     last: Option<u32>,
+    len: usize,
     area_len: usize,
     area: &'bump mut [u32],
     head: Option<NonNull<Node>>,
@@ -56,13 +59,17 @@ struct Node {
     // assert exclusivity of the reference to perform that mutation.
     next_node: Cell<Option<NonNull<u8>>>,
     next_node_len: Cell<u32>,
+    // The smallest and largest numbers of the block,
+    // used to skip the blocks that cannot contain a number.
+    min: u32,
+    max: u32,
     num_bits: u8,
     mantissa: u8,
     bytes: [u8],
 }
 
 impl Node {
-    const BASE_SIZE: usize = mem::size_of::<(Option<NonNull<u8>>, u32, u8, u8)>();
+    const BASE_SIZE: usize = mem::size_of::<(Option<NonNull<u8>>, u32, u32, u32, u8, u8)>();
 
     #[allow(clippy::mut_from_ref)]
     fn new_in(block_size: usize, bump: &Bump) -> &mut Node {
@@ -98,6 +105,7 @@ impl<'bump, B: BitPacker> Bbbul<'bump, B> {
         Bbbul {
             bump,
             last: None,
+            len: 0,
             area_len: 0,
             area: bump.alloc_slice_fill_copy(B::BLOCK_LEN, 0),
             head: None,
@@ -123,6 +131,7 @@ impl<'bump, B: BitPacker> Bbbul<'bump, B> {
         }
 
         self.last = Some(n);
+        self.len += 1;
         self.area[self.area_len] = n;
         self.area_len += 1;
 
@@ -145,7 +154,7 @@ impl<'bump, B: BitPacker> Bbbul<'bump, B> {
             Some((_, initial)) => {
                 (0..u8::BITS as u8) // shift from 0 to 31
                     .find(|&m| {
-                        initial_from_mantissa(initial, m).map_or(false, |n| n < self.area[0])
+                        initial_from_mantissa(initial, m).is_some_and(|n| n < self.area[0])
                     })
                     .map(|m| (Some(initial), m))
                     .unwrap_or((None, u8::MAX))
@@ -159,6 +168,8 @@ impl<'bump, B: BitPacker> Bbbul<'bump, B> {
 
         let next_tail = Node::new_in(block_size, self.bump);
         debug_assert_eq!(next_tail.bytes.len(), block_size);
+        next_tail.min = self.area[0];
+        next_tail.max = self.area[self.area.len() - 1];
         next_tail.num_bits = bits;
         next_tail.mantissa = mantissa;
         debug_assert!(next_tail.next_node().is_none());
@@ -167,7 +178,7 @@ impl<'bump, B: BitPacker> Bbbul<'bump, B> {
 
         let new_initial = self.area[0];
         let initial = initial.and_then(|i| initial_from_mantissa(i, mantissa));
-        debug_assert!(initial.map_or(true, |n| n < self.area[0]));
+        debug_assert!(initial.is_none_or(|n| n < self.area[0]));
         let size = bp.compress_strictly_sorted(initial, self.area, &mut next_tail.bytes, bits);
         debug_assert_eq!(next_tail.bytes.len(), size);
 
@@ -192,6 +203,26 @@ impl<'bump, B: BitPacker> Bbbul<'bump, B> {
 
         self.area_len = 0;
     }
+
+    /// Returns `true` if the number has already been inserted in this list.
+    ///
+    /// Only the blocks whose smallest and largest numbers surround `n` are decoded.
+    pub fn contains(&self, n: u32) -> bool {
+        let head = self.head.map(|nn| unsafe { nn.as_ref() });
+        contains::<B>(head, &self.area[..self.area_len], n)
+    }
+}
+
+impl<B> Bbbul<'_, B> {
+    /// The number of numbers inserted in this list.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns wether this list is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
 }
 
 /// A frozen version of the [`Bbbul`] type.
@@ -214,18 +245,25 @@ impl<'bump, B> FrozenBbbul<'bump, B> {
 
     /// Removes all the numbers stored in this `Bbbul`.
     pub fn clear(&mut self) {
+        self.0.len = 0;
         self.0.area_len = 0;
         self.0.head = None;
         self.0.tail = None;
     }
 
+    /// The number of numbers stored in this `Bbbul`.
+    pub fn len(&self) -> usize {
+        self.0.len
+    }
+
     /// Returns wether this `Bbbul` is empty.
     pub fn is_empty(&self) -> bool {
-        self.0.area_len == 0 && self.0.head.is_some()
+        self.0.len == 0
     }
 
     /// Gives an iterator of block of integers and clears the `Bbbul` at the same time.
     pub fn iter_and_clear(&mut self) -> IterAndClear<'_, B> {
+        self.0.len = 0;
         IterAndClear {
             area_len: mem::replace(&mut self.0.area_len, 0),
             area: self.0.area,
@@ -236,6 +274,76 @@ impl<'bump, B> FrozenBbbul<'bump, B> {
     }
 }
 
+impl<B> FrozenBbbul<'_, B> {
+    /// Iterates over the packed blocks of this `Bbbul`, in insertion order, without decoding them.
+    ///
+    /// The numbers that are not packed yet are available with [`Self::unpacked`].
+    /// See [`PackedBlock`] for the layout of each block.
+    pub fn blocks(&self) -> Blocks<'_, B> {
+        Blocks {
+            head: self.0.head.map(|nn| unsafe { nn.as_ref() }),
+            _marker: marker::PhantomData,
+        }
+    }
+
+    /// The numbers that are not packed in a block yet, in insertion order.
+    pub fn unpacked(&self) -> &[u32] {
+        &self.0.area[..self.0.area_len]
+    }
+}
+
+impl<B: BitPacker> FrozenBbbul<'_, B> {
+    /// Returns `true` if the number is stored in this `Bbbul`.
+    ///
+    /// Only the blocks whose smallest and largest numbers surround `n` are decoded.
+    pub fn contains(&self, n: u32) -> bool {
+        self.0.contains(n)
+    }
+
+    /// Decodes the blocks of this `Bbbul` in parallel, on the current rayon thread pool,
+    /// and calls `consumer` with each decoded block of ordered `u32`s.
+    ///
+    /// The blocks are given to the consumer in no particular order,
+    /// and the numbers that are not packed yet are given as a last, sorted, block.
+    #[cfg(feature = "rayon")]
+    pub fn par_for_each_block<F>(&self, consumer: F)
+    where
+        F: Fn(&[u32]) + Sync,
+    {
+        use rayon::iter::{IntoParallelRefIterator as _, ParallelIterator as _};
+
+        // the buffers are sized for the largest bit packer
+        const { assert!(B::BLOCK_LEN <= MAX_BLOCK_LEN) };
+
+        // Nodes are not `Sync`, so we extract what is needed to decode
+        // each block independently before sharing them with the pool.
+        let mut previous_min = None;
+        let blocks: Vec<_> = self
+            .blocks()
+            .map(|block| {
+                let initial = previous_min.and_then(|i| initial_from_mantissa(i, block.mantissa()));
+                previous_min = Some(block.smallest());
+                (initial, block.num_bits(), block.as_bytes())
+            })
+            .collect();
+
+        blocks.par_iter().for_each_init(
+            || (B::new(), [0; MAX_BLOCK_LEN]),
+            |(bp, buffer), &(initial, num_bits, bytes)| {
+                let buffer = &mut buffer[..B::BLOCK_LEN];
+                bp.decompress_strictly_sorted(initial, bytes, buffer, num_bits);
+                consumer(buffer);
+            },
+        );
+
+        if !self.unpacked().is_empty() {
+            let mut unpacked = self.unpacked().to_vec();
+            unpacked.sort_unstable();
+            consumer(&unpacked);
+        }
+    }
+}
+
 /// # Safety
 ///
 /// - The FrozenBbbul never reallocates.
@@ -244,6 +352,15 @@ impl<'bump, B> FrozenBbbul<'bump, B> {
 /// So, it is safe to send the contained shared reference to the allocator
 unsafe impl<B> Send for FrozenBbbul<'_, B> {}
 
+impl<'bump, B> Freeze for Bbbul<'bump, B> {
+    type Frozen = FrozenBbbul<'bump, B>;
+
+    #[inline]
+    fn freeze(self) -> Self::Frozen {
+        FrozenBbbul::new(self)
+    }
+}
+
 /// An non-standard iterator over the `u32`s in the [`FrozenBbbul`] type.
 ///
 /// Returns slices of ordered `u32`s.
@@ -286,6 +403,108 @@ impl<B: BitPacker> IterAndClear<'_, B> {
     }
 }
 
+/// An iterator over the [`PackedBlock`]s of a [`FrozenBbbul`].
+pub struct Blocks<'a, B> {
+    head: Option<&'a Node>,
+    _marker: marker::PhantomData<B>,
+}
+
+impl<'a, B> Iterator for Blocks<'a, B> {
+    type Item = PackedBlock<'a, B>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let node = self.head.take()?;
+        self.head = node.next_node();
+        Some(PackedBlock {
+            node,
+            _marker: marker::PhantomData,
+        })
+    }
+}
+
+/// A block of [`BitPacker::BLOCK_LEN`] numbers, bit-packed with the [`BitPacker`] `B`.
+///
+/// The block contains the numbers in ascending order, bit-packed with
+/// [`BitPacker::compress_strictly_sorted`] using [`Self::num_bits`] bits per number.
+/// The initial value given to the compression is derived from the smallest number of the
+/// previous block, right-shifted by [`Self::mantissa`] bits, or is `None` if the mantissa is
+/// [`u8::MAX`] or if this is the first block.
+///
+/// Decoding a block therefore requires the smallest number of the previous block.
+pub struct PackedBlock<'a, B> {
+    node: &'a Node,
+    _marker: marker::PhantomData<B>,
+}
+
+impl<'a, B: BitPacker> PackedBlock<'a, B> {
+    /// The packed bytes of the block.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        &self.node.bytes
+    }
+
+    /// The smallest number of the block.
+    pub fn smallest(&self) -> u32 {
+        self.node.min
+    }
+
+    /// The largest number of the block.
+    pub fn largest(&self) -> u32 {
+        self.node.max
+    }
+
+    /// The number of bits used for each number.
+    pub fn num_bits(&self) -> u8 {
+        self.node.num_bits
+    }
+
+    /// The number of bits the smallest number of the previous block is right-shifted by
+    /// to obtain the initial value of the compression, or [`u8::MAX`] if there is no initial value.
+    pub fn mantissa(&self) -> u8 {
+        self.node.mantissa
+    }
+
+    /// Decodes the block into `output`, given the smallest number of the previous block.
+    ///
+    /// # Panics
+    ///
+    /// - if `output` is not exactly [`BitPacker::BLOCK_LEN`] long.
+    pub fn decode(&self, previous_smallest: Option<u32>, output: &mut [u32]) {
+        let initial = previous_smallest.and_then(|i| initial_from_mantissa(i, self.node.mantissa));
+        B::new().decompress_strictly_sorted(initial, &self.node.bytes, output, self.node.num_bits);
+    }
+}
+
+/// The largest [`BitPacker::BLOCK_LEN`] of the provided bit packers.
+///
+/// The buffers of this size are checked at compile time to fit the block of the bit packer in use.
+pub(crate) const MAX_BLOCK_LEN: usize = BitPacker8x::BLOCK_LEN;
+
+fn contains<B: BitPacker>(head: Option<&Node>, unpacked: &[u32], n: u32) -> bool {
+    if unpacked.contains(&n) {
+        return true;
+    }
+
+    // the buffer is sized for the largest bit packer
+    const { assert!(B::BLOCK_LEN <= MAX_BLOCK_LEN) };
+    let mut buffer = [0; MAX_BLOCK_LEN];
+    let buffer = &mut buffer[..B::BLOCK_LEN];
+    let mut previous_min = None;
+    let mut next = head;
+    while let Some(node) = next {
+        if (node.min..=node.max).contains(&n) {
+            let initial = previous_min.and_then(|i| initial_from_mantissa(i, node.mantissa));
+            B::new().decompress_strictly_sorted(initial, &node.bytes, buffer, node.num_bits);
+            if buffer.binary_search(&n).is_ok() {
+                return true;
+            }
+        }
+        previous_min = Some(node.min);
+        next = node.next_node();
+    }
+
+    false
+}
+
 fn initial_from_mantissa(initial: u32, mantissa: u8) -> Option<u32> {
     1u32.checked_shl(mantissa as u32).map(|d| initial / d)
 }
@@ -295,8 +514,8 @@ unsafe fn fatten(data: NonNull<u8>, len: usize) -> *mut Node {
     ptr::slice_from_raw_parts_mut(data.as_ptr(), len) as *mut Node
 }
 
-/// Make sure that Node base size has a size of 16 bytes.
-const _NODE_SIZE_16: () = if Node::BASE_SIZE != 16 {
+/// Make sure that Node base size has a size of 24 bytes.
+const _NODE_SIZE_24: () = if Node::BASE_SIZE != 24 {
     unreachable!()
 };
 
@@ -337,6 +556,87 @@ mod tests {
         assert!(expected.is_empty());
     }
 
+    #[test]
+    fn packed_blocks() {
+        let bump = bumpalo::Bump::new();
+        let mut bbbul = Bbbul::<BitPacker4x>::new_in(&bump);
+
+        for n in 0..1000 {
+            bbbul.insert(n);
+        }
+
+        let frozen = FrozenBbbul::new(bbbul);
+        let mut numbers = Vec::new();
+        let mut block = vec![0; BitPacker4x::BLOCK_LEN];
+        let mut previous_smallest = None;
+        for packed in frozen.blocks() {
+            assert_eq!(
+                packed.as_bytes().len(),
+                BitPacker4x::compressed_block_size(packed.num_bits())
+            );
+            packed.decode(previous_smallest, &mut block);
+            previous_smallest = Some(block[0]);
+            numbers.extend_from_slice(&block);
+        }
+        numbers.extend_from_slice(frozen.unpacked());
+        assert_eq!(numbers, (0..1000).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn contains_and_len() {
+        let bump = bumpalo::Bump::new();
+        let mut bbbul = Bbbul::<BitPacker1x>::new_in(&bump);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        assert!(bbbul.is_empty());
+        let mut expected = HashSet::new();
+        for _ in 0..1000 {
+            let n = rng.next_u32() % 5000;
+            if !bbbul.contains(n) {
+                assert!(expected.insert(n), "{n} is already present");
+                bbbul.insert(n);
+            }
+        }
+        assert_eq!(bbbul.len(), expected.len());
+
+        let mut frozen = FrozenBbbul::new(bbbul);
+        assert!(!frozen.is_empty());
+        assert_eq!(frozen.len(), expected.len());
+        for n in 0..5000 {
+            assert_eq!(frozen.contains(n), expected.contains(&n), "contains {n}");
+        }
+
+        frozen.iter_and_clear();
+        assert!(frozen.is_empty());
+        assert!(!frozen.contains(*expected.iter().next().unwrap()));
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_for_each_block() {
+        let bump = bumpalo::Bump::new();
+        let mut bbbul = Bbbul::<BitPacker4x>::new_in(&bump);
+        let mut rng = rand::rngs::StdRng::seed_from_u64(42);
+
+        let mut expected = HashSet::new();
+        for _ in 0..10_000 {
+            let n = rng.next_u32();
+            if expected.insert(n) {
+                bbbul.insert(n);
+            }
+        }
+
+        let frozen = FrozenBbbul::new(bbbul);
+        let decoded = std::sync::Mutex::new(Vec::new());
+        frozen.par_for_each_block(|block| {
+            assert!(block.windows(2).all(|w| w[0] < w[1]));
+            decoded.lock().unwrap().extend_from_slice(block);
+        });
+        let decoded = decoded.into_inner().unwrap();
+        assert_eq!(decoded.len(), expected.len());
+        assert_eq!(decoded.into_iter().collect::<HashSet<_>>(), expected);
+    }
+
     #[test]
     fn basic_reverse() {
         let bump = bumpalo::Bump::new();
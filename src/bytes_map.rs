@@ -0,0 +1,229 @@
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::Deref;
+
+use bumpalo::collections::Vec as BVec;
+use bumpalo::Bump;
+use hashbrown::DefaultHashBuilder;
+use serde_json::value::RawValue;
+
+use crate::map::FrozenMap;
+use crate::Freeze;
+
+/// An order-preserving map keyed by arbitrary bytes, optimized for iteration over insertion.
+///
+/// This is the sibling of [`crate::RawMap`] for keys that are not necessarily valid UTF-8,
+/// such as binary key encodings. It follows the same design: a vector containing references
+/// to *both* the keys and data, and a hashmap meant to provide constant time access to the elements.
+///
+/// All allocations happen in the associated [`Bump`].
+pub struct RawBytesMap<'bump, S = DefaultHashBuilder> {
+    data: BVec<'bump, (&'bump [u8], &'bump RawValue)>,
+    cache: hashbrown::HashMap<&'bump [u8], usize, S, &'bump Bump>,
+}
+
+impl<'bump> RawBytesMap<'bump, DefaultHashBuilder> {
+    /// Constructs an empty map backed by the specified bump allocator.
+    #[inline]
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        Self {
+            data: BVec::new_in(bump),
+            cache: hashbrown::HashMap::new_in(bump),
+        }
+    }
+}
+
+impl<'bump, S: BuildHasher> RawBytesMap<'bump, S> {
+    /// Inserts a new (key, value) pair in the map.
+    ///
+    /// If the key already exists, then the order of the first insertion of the key is maintained, the value is updated,
+    /// and the previous value is returned.
+    #[inline]
+    pub fn insert(&mut self, key: &'bump [u8], value: &'bump RawValue) -> Option<&'bump RawValue> {
+        match self.cache.entry(key) {
+            hashbrown::hash_map::Entry::Occupied(entry) => {
+                let index = entry.get();
+                Some(std::mem::replace(
+                    &mut self.data.get_mut(*index).unwrap().1,
+                    value,
+                ))
+            }
+            hashbrown::hash_map::Entry::Vacant(entry) => {
+                let index = self.data.len();
+                self.data.push((key, value));
+                entry.insert(index);
+                None
+            }
+        }
+    }
+
+    /// Retrieves the value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &[u8]) -> Option<&'bump RawValue> {
+        let index = self.cache.get(key)?;
+        self.data.get(*index).map(|(_, v)| *v)
+    }
+
+    /// Retrieves the index of a key in the data slice, if present.
+    #[inline]
+    pub fn get_index(&self, key: &[u8]) -> Option<usize> {
+        self.cache.get(key).copied()
+    }
+
+    /// Reserves capacity for at least additional more elements to be inserted in the map.
+    ///
+    /// # Panics
+    ///
+    /// - if the new capacity exceeds [`isize::MAX`].
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.data.reserve(additional);
+        self.cache.reserve(additional);
+    }
+}
+
+impl<'bump, S> RawBytesMap<'bump, S> {
+    /// Constructs an empty map backed by the specified bump allocator.
+    #[inline]
+    pub fn with_hasher_in(hash_builder: S, bump: &'bump Bump) -> Self {
+        Self {
+            data: BVec::new_in(bump),
+            cache: hashbrown::HashMap::with_hasher_in(hash_builder, bump),
+        }
+    }
+
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns the underlying vec as a slice.
+    #[inline]
+    pub fn as_slice(&self) -> &[(&'bump [u8], &'bump RawValue)] {
+        self.data.as_slice()
+    }
+
+    /// Iterates over the (key, value) pairs of the map in first-insertion order.
+    #[inline]
+    pub fn iter(&self) -> std::iter::Copied<std::slice::Iter<'_, (&'bump [u8], &'bump RawValue)>> {
+        self.data.iter().copied()
+    }
+
+    /// Consumes `self` and returns the underlying vec as a bump slice.
+    #[inline]
+    pub fn into_bump_slice(self) -> &'bump [(&'bump [u8], &'bump RawValue)] {
+        self.data.into_bump_slice()
+    }
+
+    /// Makes this map [`Send`] by forbidding any future modifications.
+    #[inline]
+    pub fn freeze(&mut self) -> FrozenRawBytesMap<'_, 'bump, S> {
+        FrozenRawBytesMap::new(self)
+    }
+
+    /// Returns a shared reference to the allocator backing this map.
+    #[inline]
+    pub fn bump(&self) -> &'bump Bump {
+        self.data.bump()
+    }
+}
+
+impl<'a, 'bump, S: Send> Freeze for &'a mut RawBytesMap<'bump, S> {
+    type Frozen = FrozenRawBytesMap<'a, 'bump, S>;
+
+    #[inline]
+    fn freeze(self) -> Self::Frozen {
+        FrozenRawBytesMap::new(self)
+    }
+}
+
+impl<'bump, S> Deref for RawBytesMap<'bump, S> {
+    type Target = [(&'bump [u8], &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<S> fmt::Debug for RawBytesMap<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RawBytesMap")
+            .field("data", &self.data)
+            .finish()
+    }
+}
+
+/// A view into a [`RawBytesMap`] that prevents insertions, but can be sent between threads safely.
+pub struct FrozenRawBytesMap<'a, 'bump, S> {
+    data: &'a [(&'bump [u8], &'bump RawValue)],
+    cache: FrozenMap<'a, 'bump, &'bump [u8], usize, S>,
+}
+
+impl<'a, 'bump, S> FrozenRawBytesMap<'a, 'bump, S> {
+    /// Makes the passed map [`Send`] by preventing any future modifications.
+    #[inline]
+    pub fn new(map: &'a mut RawBytesMap<'bump, S>) -> Self {
+        Self {
+            data: map.data.as_slice(),
+            cache: FrozenMap::new(&mut map.cache),
+        }
+    }
+
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    /// Returns a reference to the underlying slice.
+    #[inline]
+    pub fn as_slice(&self) -> &'a [(&'bump [u8], &'bump RawValue)] {
+        self.data
+    }
+}
+
+impl<'bump, S: BuildHasher> FrozenRawBytesMap<'_, 'bump, S> {
+    /// Retrieves the value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &[u8]) -> Option<&'bump RawValue> {
+        let index = self.cache.get(key)?;
+        self.data.get(*index).map(|(_, v)| *v)
+    }
+
+    /// Retrieves the index of a key in the data slice, if present.
+    #[inline]
+    pub fn get_index(&self, key: &[u8]) -> Option<usize> {
+        self.cache.get(key).copied()
+    }
+}
+
+impl<'bump, S> Deref for FrozenRawBytesMap<'_, 'bump, S> {
+    type Target = [(&'bump [u8], &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.data
+    }
+}
+
+impl<S> fmt::Debug for FrozenRawBytesMap<'_, '_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrozenRawBytesMap")
+            .field("data", &self.data)
+            .finish()
+    }
+}
@@ -0,0 +1,279 @@
+use std::hash::BuildHasher;
+
+use bumpalo::Bump;
+use serde_json::value::RawValue;
+
+use crate::map::FrozenRawMap;
+use crate::value::Kind;
+use crate::{Error, RawMap};
+
+/// The type of the values of a [`Column`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnType {
+    /// JSON booleans.
+    Boolean,
+    /// JSON numbers that fit in an `i64`.
+    Int64,
+    /// JSON numbers, as `f64`.
+    Float64,
+    /// JSON strings, with 64-bit offsets.
+    LargeUtf8,
+}
+
+/// The values of a column of a document batch, laid out as the buffers of an Arrow array.
+///
+/// The value of a null entry is the default value of the type, or an empty string.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ColumnValues {
+    /// The values of a [`ColumnType::Boolean`] column.
+    Boolean(Bitmap),
+    /// The values of a [`ColumnType::Int64`] column.
+    Int64(Vec<i64>),
+    /// The values of a [`ColumnType::Float64`] column.
+    Float64(Vec<f64>),
+    /// The values of a [`ColumnType::LargeUtf8`] column.
+    LargeUtf8 {
+        /// The `len + 1` offsets of the strings in `data`.
+        offsets: Vec<i64>,
+        /// The concatenated UTF-8 bytes of the strings.
+        data: Vec<u8>,
+    },
+}
+
+/// A column of a document batch, with one entry per document.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Column {
+    /// Unset for the documents where the value is missing or `null`.
+    pub validity: Bitmap,
+    /// The values of the column.
+    pub values: ColumnValues,
+}
+
+impl Column {
+    fn with_capacity(ty: ColumnType, capacity: usize) -> Self {
+        let values = match ty {
+            ColumnType::Boolean => ColumnValues::Boolean(Bitmap::with_capacity(capacity)),
+            ColumnType::Int64 => ColumnValues::Int64(Vec::with_capacity(capacity)),
+            ColumnType::Float64 => ColumnValues::Float64(Vec::with_capacity(capacity)),
+            ColumnType::LargeUtf8 => ColumnValues::LargeUtf8 {
+                offsets: vec![0],
+                data: Vec::new(),
+            },
+        };
+        Self {
+            validity: Bitmap::with_capacity(capacity),
+            values,
+        }
+    }
+
+    /// The number of entries in the column.
+    pub fn len(&self) -> usize {
+        self.validity.len()
+    }
+
+    /// `true` if the column has no entries.
+    pub fn is_empty(&self) -> bool {
+        self.validity.is_empty()
+    }
+
+    fn push_null(&mut self) {
+        self.validity.push(false);
+        match &mut self.values {
+            ColumnValues::Boolean(values) => values.push(false),
+            ColumnValues::Int64(values) => values.push(0),
+            ColumnValues::Float64(values) => values.push(0.0),
+            ColumnValues::LargeUtf8 { offsets, data } => offsets.push(data.len() as i64),
+        }
+    }
+
+    fn push(&mut self, raw: &RawValue) -> Result<(), Error> {
+        match &mut self.values {
+            ColumnValues::Boolean(values) => values.push(parse(raw, Kind::Bool)?),
+            ColumnValues::Int64(values) => values.push(parse(raw, Kind::Number)?),
+            ColumnValues::Float64(values) => values.push(parse(raw, Kind::Number)?),
+            ColumnValues::LargeUtf8 { offsets, data } => {
+                let s: std::borrow::Cow<str> = parse(raw, Kind::String)?;
+                data.extend_from_slice(s.as_bytes());
+                // a vector never holds more than `isize::MAX` bytes
+                offsets.push(data.len() as i64);
+            }
+        }
+        self.validity.push(true);
+        Ok(())
+    }
+}
+
+/// A packed sequence of bits, laid out as an Arrow bitmap: the bit `i` is the bit `i % 8` of the byte `i / 8`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Bitmap {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Bitmap {
+    fn with_capacity(capacity: usize) -> Self {
+        Self {
+            bytes: Vec::with_capacity(capacity.div_ceil(8)),
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, bit: bool) {
+        if self.len % 8 == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().unwrap() |= 1 << (self.len % 8);
+        }
+        self.len += 1;
+    }
+
+    /// The number of bits in the bitmap.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// `true` if the bitmap has no bits.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bit at `index`, or `None` if it is out of bounds.
+    #[inline]
+    pub fn get(&self, index: usize) -> Option<bool> {
+        (index < self.len).then(|| self.bytes[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Iterates over the bits of the bitmap.
+    pub fn iter(&self) -> impl Iterator<Item = bool> + '_ {
+        (0..self.len).map(|index| self.bytes[index / 8] & (1 << (index % 8)) != 0)
+    }
+
+    /// Returns the packed bytes, the unused bits of the last byte are unset.
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl Column {
+    /// Converts the column into an Arrow array, moving its buffers without copying them.
+    ///
+    /// The [`ColumnType::LargeUtf8`] columns are converted to `LargeStringArray`s.
+    /// The validity bitmap is omitted when the column has no null entries.
+    pub fn into_arrow(self) -> arrow_array::ArrayRef {
+        use std::sync::Arc;
+
+        use arrow_array::{BooleanArray, Float64Array, Int64Array, LargeStringArray};
+        use arrow_buffer::{Buffer, NullBuffer, OffsetBuffer, ScalarBuffer};
+
+        let nulls = Some(NullBuffer::new(self.validity.into_arrow()))
+            .filter(|nulls| nulls.null_count() > 0);
+        match self.values {
+            ColumnValues::Boolean(values) => {
+                Arc::new(BooleanArray::new(values.into_arrow(), nulls))
+            }
+            ColumnValues::Int64(values) => {
+                Arc::new(Int64Array::new(ScalarBuffer::from(values), nulls))
+            }
+            ColumnValues::Float64(values) => {
+                Arc::new(Float64Array::new(ScalarBuffer::from(values), nulls))
+            }
+            ColumnValues::LargeUtf8 { offsets, data } => Arc::new(LargeStringArray::new(
+                // the offsets start at 0 and are increasing
+                OffsetBuffer::new(ScalarBuffer::from(offsets)),
+                Buffer::from_vec(data),
+                nulls,
+            )),
+        }
+    }
+}
+
+#[cfg(feature = "arrow")]
+impl Bitmap {
+    fn into_arrow(self) -> arrow_buffer::BooleanBuffer {
+        arrow_buffer::BooleanBuffer::new(arrow_buffer::Buffer::from_vec(self.bytes), 0, self.len)
+    }
+}
+
+fn parse<'a, T: serde::Deserialize<'a>>(raw: &'a RawValue, expected: Kind) -> Result<T, Error> {
+    serde_json::from_str(raw.get()).map_err(|error| Error::from_raw_value(raw, expected, error))
+}
+
+/// Extracts columns from a batch of documents, one entry per document in each column.
+///
+/// Each column is described by its dotted path in the documents, and by the type of its values.
+/// The missing and `null` values are null entries of the columns.
+/// The values are parsed straight from the raw JSON of the documents.
+///
+/// # Errors
+///
+/// - if a value is not of the type of its column. The path of the error is
+///   the index of the document followed by the path of the column.
+pub fn to_columns<S: BuildHasher>(
+    documents: &[FrozenRawMap<'_, '_, S>],
+    columns: &[(&str, ColumnType)],
+) -> Result<Vec<Column>, Error> {
+    let mut output: Vec<_> = columns
+        .iter()
+        .map(|&(_, ty)| Column::with_capacity(ty, documents.len()))
+        .collect();
+    let mut bump = Bump::new();
+    for (index, document) in documents.iter().enumerate() {
+        for (&(path, _), column) in columns.iter().zip(&mut output) {
+            let value = resolve(document, path, &bump)
+                .map_err(|error| error.with_path(|| format!("[{index}].{path}")))?;
+            match value {
+                Some(raw) if raw.get() != "null" => column
+                    .push(raw)
+                    .map_err(|error| error.with_path(|| format!("[{index}].{path}")))?,
+                _ => column.push_null(),
+            }
+        }
+        bump.reset();
+    }
+    Ok(output)
+}
+
+/// Extracts Arrow arrays from a batch of documents, one entry per document in each array.
+///
+/// See [`to_columns`] and [`Column::into_arrow`].
+///
+/// # Errors
+///
+/// - if a value is not of the type of its column. The path of the error is
+///   the index of the document followed by the path of the column.
+#[cfg(feature = "arrow")]
+pub fn to_arrow_arrays<S: BuildHasher>(
+    documents: &[FrozenRawMap<'_, '_, S>],
+    columns: &[(&str, ColumnType)],
+) -> Result<Vec<arrow_array::ArrayRef>, Error> {
+    let columns = to_columns(documents, columns)?;
+    Ok(columns.into_iter().map(Column::into_arrow).collect())
+}
+
+/// Resolves a dotted path in a document, parsing the nested objects in `bump`.
+fn resolve<'a, 'doc: 'a, S: BuildHasher>(
+    document: &FrozenRawMap<'_, 'doc, S>,
+    path: &str,
+    bump: &'a Bump,
+) -> Result<Option<&'a RawValue>, Error> {
+    let mut segments = path.split('.');
+    let first = segments.next().unwrap_or_default();
+    let Some(mut value) = document.get(first) else {
+        return Ok(None);
+    };
+    for segment in segments {
+        if Kind::of(value) != Kind::Object {
+            return Ok(None);
+        }
+        match RawMap::from_raw_value(value, bump)?.get(segment) {
+            Some(nested) => value = nested,
+            None => return Ok(None),
+        }
+    }
+    Ok(Some(value))
+}
@@ -1,5 +1,12 @@
+use std::marker::PhantomData;
+
 use bumpalo::Bump;
-use serde::de::{DeserializeSeed, Visitor};
+use serde::de::{DeserializeSeed, Error as _, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+
+use crate::interner::Interner;
+use crate::RawMap;
 
 /// A type that can be used as a [`DeserializeSeed`] to deserialize strings that are
 /// either allocated into a [`Bump`] or reference the source data.
@@ -37,3 +44,119 @@ impl<'de, 'bump: 'de> DeserializeSeed<'de> for BumpStrSeed<'bump> {
         deserializer.deserialize_str(BumpVisitor(self.0))
     }
 }
+
+/// A type that can be used as a [`DeserializeSeed`] to deserialize strings that are
+/// interned in an [`Interner`].
+///
+/// Deserializing the same string multiple times returns the same allocation.
+pub struct InternedStrSeed<'a, 'bump>(pub &'a mut Interner<'bump>);
+
+impl<'de, 'bump> DeserializeSeed<'de> for InternedStrSeed<'_, 'bump> {
+    type Value = &'bump str;
+
+    #[inline]
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        struct InternerVisitor<'a, 'bump>(&'a mut Interner<'bump>);
+        impl<'bump> Visitor<'_> for InternerVisitor<'_, 'bump> {
+            type Value = &'bump str;
+
+            fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+                write!(formatter, "expecting a string")
+            }
+
+            fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+            where
+                E: serde::de::Error,
+            {
+                Ok(self.0.interned(v))
+            }
+        }
+        deserializer.deserialize_str(InternerVisitor(self.0))
+    }
+}
+
+/// A type that can be used as a [`DeserializeSeed`] to deserialize a struct `T` from a JSON object,
+/// capturing the fields that are not fields of `T` in a [`RawMap`] instead of dropping them.
+///
+/// The fields of `T` are the ones it declares when it is deserialized as a struct,
+/// as structs deriving [`Deserialize`] do. Every field of the object is captured if `T`
+/// is not deserialized as a struct, for example if it uses `#[serde(flatten)]`.
+pub struct RemainingFieldsSeed<'bump, T> {
+    bump: &'bump Bump,
+    _marker: PhantomData<T>,
+}
+
+impl<'bump, T> RemainingFieldsSeed<'bump, T> {
+    /// Constructs a seed capturing the remaining fields in `bump`.
+    #[inline]
+    pub fn new(bump: &'bump Bump) -> Self {
+        Self {
+            bump,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'bump, T> DeserializeSeed<'bump> for RemainingFieldsSeed<'bump, T>
+where
+    T: Deserialize<'bump>,
+{
+    type Value = (T, RawMap<'bump>);
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'bump>,
+    {
+        let raw: &'bump RawValue = Deserialize::deserialize(deserializer)?;
+        let mut fields: &[&str] = &[];
+        let value = T::deserialize(FieldsRecorder {
+            raw,
+            fields: &mut fields,
+        })
+        .map_err(D::Error::custom)?;
+        let mut remaining = RawMap::from_raw_value(raw, self.bump).map_err(D::Error::custom)?;
+        remaining.retain_keys(|key| !fields.contains(&key));
+        Ok((value, remaining))
+    }
+}
+
+/// A deserializer recording the fields declared by the struct deserialized from a raw value.
+struct FieldsRecorder<'a, 'bump> {
+    raw: &'bump RawValue,
+    fields: &'a mut &'static [&'static str],
+}
+
+impl<'de> Deserializer<'de> for FieldsRecorder<'_, 'de> {
+    type Error = serde_json::Error;
+
+    #[inline]
+    fn deserialize_any<V>(self, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        self.raw.deserialize_any(visitor)
+    }
+
+    #[inline]
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        *self.fields = fields;
+        self.raw.deserialize_struct(name, fields, visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf option unit unit_struct newtype_struct seq tuple
+        tuple_struct map enum identifier ignored_any
+    }
+}
@@ -0,0 +1,241 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use bumpalo::Bump;
+use hashbrown::DefaultHashBuilder;
+
+use crate::bbbul::{BitPacker, MAX_BLOCK_LEN};
+use crate::{Bbbul, Freeze, FrozenBbbul};
+
+/// Accumulates the docids associated with keys from several threads, in [`Bbbul`]s.
+///
+/// The accumulator is made of one [`DocidsShard`] per bump. Each indexing thread takes one shard
+/// and pushes docids into it, allocating only in the bump of that shard.
+/// Once the threads are done, [`Self::freeze`] merges the shards into a [`FrozenDocids`].
+///
+/// ```
+/// use bumpalo::Bump;
+/// use bumparaw_collections::docids::DocidsAccumulator;
+/// use bitpacking::BitPacker4x;
+///
+/// let mut bumps: Vec<_> = (0..4).map(|_| Bump::new()).collect();
+/// let mut accumulator = DocidsAccumulator::<&str, BitPacker4x>::new_in(&mut bumps);
+/// std::thread::scope(|scope| {
+///     for (thread, shard) in accumulator.shards_mut().iter_mut().enumerate() {
+///         scope.spawn(move || {
+///             for docid in (thread as u32 * 1000)..(thread as u32 + 1) * 1000 {
+///                 let key = if docid % 2 == 0 { "even" } else { "odd" };
+///                 shard.insert(key, docid);
+///             }
+///         });
+///     }
+/// });
+///
+/// let frozen = accumulator.freeze();
+/// let even = frozen.get("even").unwrap().to_sorted_vec();
+/// assert_eq!(even, (0..4000).step_by(2).collect::<Vec<_>>());
+/// ```
+pub struct DocidsAccumulator<'bump, K, B> {
+    shards: Vec<DocidsShard<'bump, K, B>>,
+}
+
+impl<'bump, K, B> DocidsAccumulator<'bump, K, B> {
+    /// Constructs an accumulator with one shard per bump.
+    ///
+    /// # Panics
+    ///
+    /// - if `bumps` is empty.
+    pub fn new_in(bumps: &'bump mut [Bump]) -> Self {
+        assert!(!bumps.is_empty(), "at least one bump is required");
+        let shards = bumps
+            .iter_mut()
+            .map(|bump| {
+                let bump: &'bump Bump = bump;
+                DocidsShard {
+                    bump,
+                    docids: hashbrown::HashMap::new_in(bump),
+                }
+            })
+            .collect();
+        Self { shards }
+    }
+
+    /// The shards of the accumulator, to be handed to one thread each.
+    #[inline]
+    pub fn shards_mut(&mut self) -> &mut [DocidsShard<'bump, K, B>] {
+        &mut self.shards
+    }
+}
+
+impl<'bump, K: Hash + Eq, B> DocidsAccumulator<'bump, K, B> {
+    /// Merges the shards into a read-only view of the docids of each key.
+    pub fn freeze(self) -> FrozenDocids<'bump, K, B> {
+        let mut docids: HashMap<K, Vec<FrozenBbbul<'bump, B>>> = HashMap::new();
+        for shard in self.shards {
+            for (key, bbbul) in shard.docids {
+                docids.entry(key).or_default().push(FrozenBbbul::new(bbbul));
+            }
+        }
+        FrozenDocids { docids }
+    }
+}
+
+impl<'bump, K: Hash + Eq + Send, B> Freeze for DocidsAccumulator<'bump, K, B> {
+    type Frozen = FrozenDocids<'bump, K, B>;
+
+    #[inline]
+    fn freeze(self) -> Self::Frozen {
+        DocidsAccumulator::freeze(self)
+    }
+}
+
+/// A shard of a [`DocidsAccumulator`], where a single thread pushes docids.
+pub struct DocidsShard<'bump, K, B> {
+    bump: &'bump Bump,
+    docids: hashbrown::HashMap<K, Bbbul<'bump, B>, DefaultHashBuilder, &'bump Bump>,
+}
+
+impl<K: Hash + Eq, B: BitPacker> DocidsShard<'_, K, B> {
+    /// Pushes a docid for `key`.
+    ///
+    /// # Panics
+    ///
+    /// - if the docid was already pushed for this key in this shard, but not right before.
+    ///   See [`Bbbul::insert`].
+    #[inline]
+    pub fn insert(&mut self, key: K, docid: u32) {
+        let bump = self.bump;
+        self.docids
+            .entry(key)
+            .or_insert_with(|| Bbbul::new_in(bump))
+            .insert(docid);
+    }
+}
+
+impl<K, B> DocidsShard<'_, K, B> {
+    /// The number of keys that received docids in this shard.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.docids.len()
+    }
+
+    /// `true` if no docid was pushed in this shard.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.docids.is_empty()
+    }
+}
+
+/// # Safety
+///
+/// - The bump of a shard is mutably borrowed by the accumulator when the shards are created,
+///   and each shard only references its own bump.
+/// - The shard does not leak a shared reference to its bump, nor to the [`Bbbul`]s allocated in it.
+///
+/// So, moving a shard to another thread moves every reference to its bump along with it.
+unsafe impl<K: Send, B> Send for DocidsShard<'_, K, B> {}
+
+/// The docids of each key accumulated by a [`DocidsAccumulator`].
+pub struct FrozenDocids<'bump, K, B> {
+    docids: HashMap<K, Vec<FrozenBbbul<'bump, B>>>,
+}
+
+impl<'bump, K: Hash + Eq, B> FrozenDocids<'bump, K, B> {
+    /// Retrieves the docids of a key, if any were pushed.
+    #[inline]
+    pub fn get<Q>(&self, key: &Q) -> Option<MergedDocids<'_, 'bump, B>>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let parts = self.docids.get(key)?;
+        Some(MergedDocids { parts })
+    }
+}
+
+impl<'bump, K, B> FrozenDocids<'bump, K, B> {
+    /// Iterates over the keys and their docids, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, MergedDocids<'_, 'bump, B>)> {
+        self.docids
+            .iter()
+            .map(|(key, parts)| (key, MergedDocids { parts }))
+    }
+
+    /// The number of keys.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.docids.len()
+    }
+
+    /// `true` if there are no keys.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.docids.is_empty()
+    }
+}
+
+/// The docids of a key, pushed by the shards of a [`DocidsAccumulator`].
+#[derive(Clone, Copy)]
+pub struct MergedDocids<'a, 'bump, B> {
+    parts: &'a [FrozenBbbul<'bump, B>],
+}
+
+impl<B> MergedDocids<'_, '_, B> {
+    /// The number of docids pushed by all the shards.
+    ///
+    /// A docid pushed by several shards is counted once per shard.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.parts.iter().map(FrozenBbbul::len).sum()
+    }
+
+    /// `true` if there are no docids.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.parts.iter().all(FrozenBbbul::is_empty)
+    }
+}
+
+impl<B: BitPacker> MergedDocids<'_, '_, B> {
+    /// Returns `true` if any shard pushed the docid.
+    pub fn contains(&self, docid: u32) -> bool {
+        self.parts.iter().any(|part| part.contains(docid))
+    }
+
+    /// Decodes the docids and calls `consumer` with each block of ordered docids.
+    ///
+    /// The blocks are not ordered relative to each other, and a docid pushed by several shards
+    /// appears in several blocks.
+    pub fn for_each_block<F>(&self, mut consumer: F)
+    where
+        F: FnMut(&[u32]),
+    {
+        // the buffer is sized for the largest bit packer
+        const { assert!(B::BLOCK_LEN <= MAX_BLOCK_LEN) };
+        let mut buffer = [0; MAX_BLOCK_LEN];
+        let buffer = &mut buffer[..B::BLOCK_LEN];
+        for part in self.parts {
+            let mut previous_smallest = None;
+            for block in part.blocks() {
+                block.decode(previous_smallest, buffer);
+                previous_smallest = Some(block.smallest());
+                consumer(buffer);
+            }
+            if !part.unpacked().is_empty() {
+                let mut unpacked = part.unpacked().to_vec();
+                unpacked.sort_unstable();
+                consumer(&unpacked);
+            }
+        }
+    }
+
+    /// Decodes the docids into a sorted vector, without duplicates.
+    pub fn to_sorted_vec(&self) -> Vec<u32> {
+        let mut docids = Vec::with_capacity(self.len());
+        self.for_each_block(|block| docids.extend_from_slice(block));
+        docids.sort_unstable();
+        docids.dedup();
+        docids
+    }
+}
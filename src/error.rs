@@ -0,0 +1,234 @@
+use std::fmt;
+
+use serde_json::value::RawValue;
+
+use crate::value::Kind;
+
+/// The error type of the collections of this crate.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// The JSON data could not be parsed.
+    Parse {
+        /// The path of the malformed value in the document, if known.
+        path: Option<String>,
+        /// The byte offset of the error in the input, if known.
+        offset: Option<usize>,
+        /// The underlying parsing error.
+        source: serde_json::Error,
+    },
+    /// The JSON data is valid, but could not be deserialized as the requested type.
+    Deserialize {
+        /// The path of the value in the document, if known.
+        path: Option<String>,
+        /// The underlying deserialization error.
+        source: serde_json::Error,
+    },
+    /// The JSON value is not of the expected kind.
+    WrongKind {
+        /// The path of the value in the document, if known.
+        path: Option<String>,
+        /// The expected kind of value.
+        expected: Kind,
+        /// The actual kind of value.
+        found: Kind,
+    },
+    /// A key appears multiple times in the same object.
+    DuplicateKey {
+        /// The duplicated key.
+        key: String,
+    },
+    /// The keys of entries are not sorted.
+    UnsortedKeys {
+        /// The index of the first entry whose key is smaller than the key before it.
+        index: usize,
+    },
+    /// An entry was rejected by a validation callback.
+    Rejected {
+        /// The key of the rejected entry.
+        key: String,
+        /// The reason given by the callback.
+        reason: Box<dyn std::error::Error + Send + Sync>,
+    },
+    /// A document of a batch has no primary key, or its primary key is not a string nor an integer.
+    InvalidPrimaryKey {
+        /// The path of the primary key.
+        primary_key: String,
+        /// The index of the document in the batch.
+        index: usize,
+        /// The JSON value of the primary key, `None` if it is missing.
+        value: Option<String>,
+    },
+    /// The nesting depth of the data exceeds the allowed maximum.
+    DepthExceeded {
+        /// The maximum allowed depth.
+        max_depth: usize,
+    },
+    /// The data requires more memory than the allowed budget.
+    BudgetExceeded {
+        /// The allowed budget, in bytes.
+        budget: usize,
+    },
+    /// An allocation failed in the bump allocator.
+    Alloc,
+    /// Reading the data failed.
+    Io(std::io::Error),
+}
+
+impl Error {
+    /// Wraps an error that occurred while parsing `raw` as a value of the `expected` kind.
+    ///
+    /// As raw values are always valid JSON, the error is reported as [`Error::WrongKind`]
+    /// if the raw value is not of the expected kind.
+    pub(crate) fn from_raw_value(
+        raw: &RawValue,
+        expected: Kind,
+        source: serde_json::Error,
+    ) -> Self {
+        let found = Kind::of(raw);
+        if found == expected {
+            source.into()
+        } else {
+            Error::WrongKind {
+                path: None,
+                expected,
+                found,
+            }
+        }
+    }
+
+    /// Wraps an error that occurred while parsing `input`.
+    ///
+    /// The byte offset of a parsing error is computed from the line and column reported by `serde_json`.
+    pub(crate) fn from_input(input: &str, source: serde_json::Error) -> Self {
+        let offset = match source.classify() {
+            serde_json::error::Category::Data => return source.into(),
+            _ if source.line() == 0 => None,
+            serde_json::error::Category::Eof => Some(input.len()),
+            _ => {
+                let line_start: usize = input
+                    .split_inclusive('\n')
+                    .take(source.line() - 1)
+                    .map(str::len)
+                    .sum();
+                // the column is the 1-based byte position of the error in its line
+                Some((line_start + source.column().saturating_sub(1)).min(input.len()))
+            }
+        };
+        Error::Parse {
+            path: None,
+            offset,
+            source,
+        }
+    }
+
+    /// Sets the path of the error, if the error has one and it is not already set.
+    pub(crate) fn with_path(mut self, new_path: impl FnOnce() -> String) -> Self {
+        match &mut self {
+            Error::Parse { path, .. }
+            | Error::Deserialize { path, .. }
+            | Error::WrongKind { path, .. }
+                if path.is_none() =>
+            {
+                *path = Some(new_path());
+            }
+            _ => {}
+        }
+        self
+    }
+}
+
+impl From<serde_json::Error> for Error {
+    /// Converts the errors of well-formed JSON data to [`Error::Deserialize`],
+    /// and the others to [`Error::Parse`].
+    fn from(source: serde_json::Error) -> Self {
+        match source.classify() {
+            serde_json::error::Category::Data => Error::Deserialize { path: None, source },
+            _ => Error::Parse {
+                path: None,
+                offset: None,
+                source,
+            },
+        }
+    }
+}
+
+impl From<std::io::Error> for Error {
+    fn from(error: std::io::Error) -> Self {
+        Error::Io(error)
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Parse {
+                path,
+                offset,
+                source,
+            } => {
+                write!(f, "invalid JSON")?;
+                if let Some(path) = path {
+                    write!(f, " at `{path}`")?;
+                }
+                if let Some(offset) = offset {
+                    write!(f, " at byte offset {offset}")?;
+                }
+                write!(f, ": {source}")
+            }
+            Error::Deserialize { path, source } => {
+                write!(f, "cannot deserialize the value")?;
+                if let Some(path) = path {
+                    write!(f, " at `{path}`")?;
+                }
+                write!(f, ": {source}")
+            }
+            Error::WrongKind {
+                path,
+                expected,
+                found,
+            } => {
+                write!(f, "expected {expected}, found {found}")?;
+                if let Some(path) = path {
+                    write!(f, " at `{path}`")?;
+                }
+                Ok(())
+            }
+            Error::DuplicateKey { key } => write!(f, "duplicate key `{key}`"),
+            Error::UnsortedKeys { index } => write!(f, "keys are not sorted at index {index}"),
+            Error::DepthExceeded { max_depth } => {
+                write!(f, "nesting depth exceeds the maximum of {max_depth}")
+            }
+            Error::BudgetExceeded { budget } => {
+                write!(f, "memory budget of {budget} bytes exceeded")
+            }
+            Error::Rejected { key, reason } => write!(f, "rejected entry `{key}`: {reason}"),
+            Error::InvalidPrimaryKey {
+                primary_key,
+                index,
+                value: None,
+            } => write!(f, "missing primary key `{primary_key}` in document {index}"),
+            Error::InvalidPrimaryKey {
+                primary_key,
+                index,
+                value: Some(value),
+            } => write!(
+                f,
+                "invalid primary key `{primary_key}` in document {index}: expected a string or an integer, found `{value}`"
+            ),
+            Error::Alloc => write!(f, "allocation failure"),
+            Error::Io(error) => write!(f, "I/O error: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Parse { source, .. } | Error::Deserialize { source, .. } => Some(source),
+            Error::Rejected { reason, .. } => Some(reason.as_ref()),
+            Error::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,44 @@
+/// Converts per-thread state into a read-only view that can be sent to another thread.
+///
+/// The collections backed by a [`bumpalo::Bump`] are not [`Send`], because they hold a shared reference
+/// to their bump. Their frozen views forbid any allocation, so that they can be sent along with the results
+/// of a thread. This trait lets generic pipeline stages freeze their state without knowing its type.
+///
+/// The collections that can be used again once the view is dropped implement it for a mutable reference,
+/// such as `&mut RawMap`, and the others by value, such as [`crate::Bbbul`].
+///
+/// ```
+/// use std::sync::mpsc::{channel, Sender};
+///
+/// use bitpacking::BitPacker4x;
+/// use bumpalo::Bump;
+/// use bumparaw_collections::freeze::Freeze;
+/// use bumparaw_collections::{Bbbul, RawMap};
+///
+/// fn hand_over<T: Freeze>(state: T, sender: &Sender<T::Frozen>) {
+///     sender.send(state.freeze()).unwrap();
+/// }
+///
+/// let bump = Bump::new();
+/// let mut map = RawMap::from_raw_value(serde_json::from_str(r#"{"a": 1}"#).unwrap(), &bump).unwrap();
+/// let mut bbbul = Bbbul::<BitPacker4x>::new_in(&bump);
+/// bbbul.insert(42);
+///
+/// let (maps, frozen_maps) = channel();
+/// let (bbbuls, frozen_bbbuls) = channel();
+/// hand_over(&mut map, &maps);
+/// hand_over(bbbul, &bbbuls);
+/// std::thread::scope(|scope| {
+///     scope.spawn(move || {
+///         assert_eq!(frozen_maps.recv().unwrap().len(), 1);
+///         assert_eq!(frozen_bbbuls.recv().unwrap().len(), 1);
+///     });
+/// });
+/// ```
+pub trait Freeze {
+    /// The read-only view of the collection.
+    type Frozen: Send;
+
+    /// Freezes the collection, preventing any modification for the lifetime of the view.
+    fn freeze(self) -> Self::Frozen;
+}
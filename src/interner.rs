@@ -1,5 +1,6 @@
 use bumpalo::Bump;
-use hashbrown::HashSet;
+use hashbrown::{HashMap, HashSet};
+use serde_json::value::RawValue;
 
 /// A string interner based on [`HashSet`].
 ///
@@ -32,3 +33,63 @@ impl<'bump> Interner<'bump> {
         &self.set
     }
 }
+
+/// An interner for small raw values, such as booleans, small numbers and enum-like strings.
+///
+/// Values that are identical byte-for-byte share a single allocation.
+/// Values longer than the maximum length are copied without being interned.
+///
+/// All allocations occur in the provided [`Bump`].
+pub struct ValueInterner<'bump> {
+    map: HashMap<&'bump str, &'bump RawValue, hashbrown::DefaultHashBuilder, &'bump Bump>,
+    max_len: usize,
+}
+
+impl<'bump> ValueInterner<'bump> {
+    /// The default maximum length in bytes of the interned values.
+    pub const DEFAULT_MAX_LEN: usize = 32;
+
+    /// Constructs a new interner backed by the specified allocator.
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        Self::with_max_len_in(Self::DEFAULT_MAX_LEN, bump)
+    }
+
+    /// Constructs a new interner that only interns values up to `max_len` bytes.
+    pub fn with_max_len_in(max_len: usize, bump: &'bump Bump) -> Self {
+        Self {
+            map: HashMap::new_in(bump),
+            max_len,
+        }
+    }
+
+    /// Returns the interned version of `value`, copying it in the bump if necessary.
+    pub fn interned(&mut self, value: &RawValue) -> &'bump RawValue {
+        let bump = *self.map.allocator();
+        if value.get().len() > self.max_len {
+            return crate::value::copy_raw_value_in(value, bump);
+        }
+        match self.map.get(value.get()) {
+            Some(interned) => interned,
+            None => {
+                let interned = crate::value::copy_raw_value_in(value, bump);
+                self.map.insert(interned.get(), interned);
+                interned
+            }
+        }
+    }
+
+    /// Get the interned version of `value` if it was already interned, otherwise `None`.
+    pub fn get(&self, value: &RawValue) -> Option<&'bump RawValue> {
+        self.map.get(value.get()).copied()
+    }
+
+    /// The number of distinct interned values.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// `true` if no value was interned.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+}
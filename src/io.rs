@@ -0,0 +1,71 @@
+use std::io::{self, Read};
+use std::ops::Range;
+
+use bumpalo::collections::Vec as BVec;
+use bumpalo::Bump;
+
+/// The number of bytes requested from the reader at each read.
+pub(crate) const READ_CHUNK_LEN: usize = 8 * 1024;
+
+/// The UTF-8 encoding of the byte order mark.
+const BOM: &[u8] = b"\xEF\xBB\xBF";
+
+/// Reads all the bytes of `reader` into a buffer allocated in `bump`.
+///
+/// # Errors
+///
+/// - if reading fails.
+pub fn read_to_bump_bytes<R: Read>(mut reader: R, bump: &Bump) -> io::Result<&[u8]> {
+    let mut buffer = BVec::new_in(bump);
+    let mut len = 0;
+    loop {
+        buffer.resize(len + READ_CHUNK_LEN, 0);
+        match reader.read(&mut buffer[len..]) {
+            Ok(0) => break,
+            Ok(read) => len += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error),
+        }
+    }
+    buffer.truncate(len);
+    buffer.shrink_to_fit();
+    Ok(buffer.into_bump_slice())
+}
+
+/// Reads all the bytes of `reader` into a string allocated in `bump`.
+///
+/// A leading UTF-8 byte order mark is skipped.
+///
+/// # Errors
+///
+/// - if reading fails.
+/// - if the read bytes are not valid UTF-8.
+pub fn read_to_bump_str<R: Read>(reader: R, bump: &Bump) -> io::Result<&str> {
+    let bytes = strip_bom(read_to_bump_bytes(reader, bump)?);
+    std::str::from_utf8(bytes).map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+}
+
+/// Removes the UTF-8 byte order mark at the start of `bytes`, if any.
+#[inline]
+pub fn strip_bom(bytes: &[u8]) -> &[u8] {
+    bytes.strip_prefix(BOM).unwrap_or(bytes)
+}
+
+/// Decodes `bytes` as UTF-8 into a string allocated in `bump`, replacing the invalid sequences.
+///
+/// A leading byte order mark is skipped, and each invalid sequence is replaced by U+FFFD (`�`).
+/// Returns the decoded string along with the byte ranges of the replacement characters in it.
+pub fn decode_lossy_in<'bump>(bytes: &[u8], bump: &'bump Bump) -> (&'bump str, Vec<Range<usize>>) {
+    let bytes = strip_bom(bytes);
+    let mut output = bumpalo::collections::String::with_capacity_in(bytes.len(), bump);
+    let mut replaced = Vec::new();
+    for chunk in bytes.utf8_chunks() {
+        output.push_str(chunk.valid());
+        if !chunk.invalid().is_empty() {
+            let start = output.len();
+            output.push(char::REPLACEMENT_CHARACTER);
+            replaced.push(start..output.len());
+        }
+    }
+    (output.into_bump_str(), replaced)
+}
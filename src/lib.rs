@@ -6,21 +6,46 @@
 
 /// Contains [`allocator_api2::alloc::Allocator`] implementations for [`bumpalo`] objects.
 pub mod alloc;
+/// Deduplicates batches of documents by primary key.
+pub mod batch;
 /// Contains [`crate::bbbul::Bbbul`] and [`crate::bbbul::FrozenBbbul`] types.
 pub mod bbbul;
+/// Contains [`crate::bytes_map::RawBytesMap`] and [`crate::bytes_map::FrozenRawBytesMap`].
+pub mod bytes_map;
+/// Converts batches of documents to typed columns, laid out as Arrow arrays, and to Arrow arrays with the `arrow` feature.
+pub mod columnar;
 /// Contains advanced type for [`bumpalo`]-enabled deserialization.
 pub mod de;
-/// Contains a simple `str` interner
+/// Accumulates docids per key from several threads, in [`crate::Bbbul`]s.
+pub mod docids;
+/// Contains the [`crate::Error`] type.
+pub mod error;
+/// Contains the [`crate::freeze::Freeze`] trait, implemented by all the collections.
+pub mod freeze;
+/// Contains simple interners for `str` and raw values
 pub mod interner;
+/// Contains helpers to read data into a [`bumpalo::Bump`].
+pub mod io;
 /// Contains [`crate::map::RawMap`], [`crate::map::FrozenMap`] and associated types.
 pub mod map;
+/// Parses newline-delimited JSON (NDJSON) documents.
+pub mod ndjson;
+/// Parses relaxed JSON documents, with comments and trailing commas.
+#[cfg(feature = "relaxed")]
+pub mod relaxed;
+/// Contains [`crate::soa_map::SoaRawMap`].
+pub mod soa_map;
 /// Parses [`serde_json::value::RawValue`] in bumpalo-backed types.
 pub mod value;
 /// Contains [`crate::vec::RawVec`] and associated types.
 pub mod vec;
 
 pub use bbbul::{Bbbul, FrozenBbbul};
+pub use bytes_map::RawBytesMap;
+pub use error::Error;
+pub use freeze::Freeze;
 pub use map::RawMap;
+pub use soa_map::SoaRawMap;
 pub use value::Value;
 pub use vec::RawVec;
 
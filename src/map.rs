@@ -1,20 +1,61 @@
+use std::cmp::Ordering;
 use std::fmt;
 use std::hash::BuildHasher;
+use std::ops::Deref;
 
 use bumpalo::Bump;
 use hashbrown::DefaultHashBuilder;
-use serde::{ser::SerializeMap, Serialize};
+use serde::{de::DeserializeSeed as _, ser::SerializeMap, Deserialize, Serialize};
 use serde_json::value::RawValue;
 
 use bumpalo::collections::Vec as BVec;
 
+use crate::de::BumpStrSeed;
+use crate::interner::Interner;
+use crate::value::Kind;
+use crate::{Error, Freeze, RawVec};
+
+pub use checkpoint::Checkpoint;
+pub use editor::DocumentEditor;
+pub use entry::{
+    EntryRef, OccupiedEntry, RawEntryBuilder, RawEntryBuilderMut, RawEntryMut, RawVacantEntry,
+    VacantEntryRef,
+};
 pub use frozen::FrozenMap;
 pub use frozen::FrozenRawEntryBuilderMut;
+#[cfg(feature = "heed")]
+pub use layout::RawMapCodec;
+pub use layout::{InvalidLayoutError, RawMapBytes};
+pub use memo::MemoRawMap;
+pub use meta::RawMapMeta;
+pub use normalized::{KeyNormalizer, Lowercase, NormalizedRawMap, Trim};
+pub use preserving::PreservingRawMap;
+pub use scan::EntrySpan;
+pub use slice::FrozenRawMapSlice;
+pub use stats::MapStats;
+pub(crate) use subset::semantically_equal;
 
+mod check;
+mod checkpoint;
 mod de;
+mod editor;
+mod entry;
+mod fields;
 mod frozen;
 /// Contains iterator types and implementations for [`RawMap`].
 pub mod iter;
+mod layout;
+mod memo;
+mod merge;
+mod meta;
+mod normalized;
+mod optimize;
+mod path;
+mod preserving;
+mod scan;
+mod slice;
+mod stats;
+mod subset;
 
 /// An order-preserving map optimized for iteration over insertion.
 ///
@@ -45,6 +86,20 @@ impl<S> Serialize for RawMap<'_, S> {
     }
 }
 
+impl<S> Serialize for FrozenRawMap<'_, '_, S> {
+    #[inline]
+    fn serialize<SE>(&self, serializer: SE) -> Result<SE::Ok, SE::Error>
+    where
+        SE: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.as_slice() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
 impl<'bump> RawMap<'bump, DefaultHashBuilder> {
     /// Constructs a map from a raw value and a bump allocator.
     ///
@@ -52,11 +107,214 @@ impl<'bump> RawMap<'bump, DefaultHashBuilder> {
     ///
     /// - if the raw value cannot be parsed as a map (JSON object).
     #[inline]
-    pub fn from_raw_value(
+    pub fn from_raw_value(raw: &'bump RawValue, bump: &'bump Bump) -> Result<Self, Error> {
+        Self::from_deserializer(raw, bump)
+            .map_err(|error| Error::from_raw_value(raw, Kind::Object, error))
+    }
+
+    /// Constructs a map from a raw value and a bump allocator, interning the keys in `interner`.
+    ///
+    /// Parsing many documents with the same interner makes their identical keys share a single allocation.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a map (JSON object).
+    #[inline]
+    pub fn from_raw_value_with_interner(
         raw: &'bump RawValue,
+        interner: &mut Interner<'bump>,
         bump: &'bump Bump,
-    ) -> Result<Self, serde_json::Error> {
-        Self::from_deserializer(raw, bump)
+    ) -> Result<Self, Error> {
+        Self::from_deserializer_with_interner(raw, interner, bump)
+            .map_err(|error| Error::from_raw_value(raw, Kind::Object, error))
+    }
+
+    /// Constructs a map from a raw value and a bump allocator, keeping all the occurrences of duplicated keys.
+    ///
+    /// Iterating over or serializing the map reproduces the duplicated keys in their original order,
+    /// while lookups return the last occurrence. See [`Self::push`] for more information.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a map (JSON object).
+    #[inline]
+    pub fn from_raw_value_preserving_duplicates(
+        raw: &'bump RawValue,
+        bump: &'bump Bump,
+    ) -> Result<Self, Error> {
+        Self::from_deserializer_preserving_duplicates(raw, bump)
+            .map_err(|error| Error::from_raw_value(raw, Kind::Object, error))
+    }
+
+    /// Constructs a map from a raw value and a bump allocator, calling `validate` on each top-level entry.
+    ///
+    /// Parsing stops at the first entry rejected by `validate`, which allows checking constraints
+    /// on the keys and values while parsing instead of scanning the map again.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a map (JSON object).
+    /// - if an entry is rejected by `validate`, as an [`Error::Rejected`] error.
+    pub fn from_raw_value_with_validator<F, E>(
+        raw: &'bump RawValue,
+        bump: &'bump Bump,
+        mut validate: F,
+    ) -> Result<Self, Error>
+    where
+        F: FnMut(&str, &RawValue) -> Result<(), E>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let mut rejection = None;
+        let result = Self::from_deserializer_with_validator(
+            raw,
+            &mut |key, value| {
+                validate(key, value).map_err(|reason| {
+                    rejection = Some(Error::Rejected {
+                        key: key.to_string(),
+                        reason: reason.into(),
+                    });
+                })
+            },
+            bump,
+        );
+        match rejection {
+            Some(rejection) => Err(rejection),
+            None => result.map_err(|error| Error::from_raw_value(raw, Kind::Object, error)),
+        }
+    }
+
+    /// Constructs a map from the JSON object read from `reader`.
+    ///
+    /// The bytes are read directly into the bump allocator, and the map references them.
+    /// A leading UTF-8 byte order mark is skipped.
+    ///
+    /// # Errors
+    ///
+    /// - if reading fails or the read bytes are not valid UTF-8.
+    /// - if the read data cannot be parsed as a map (JSON object).
+    pub fn from_reader<R: std::io::Read>(reader: R, bump: &'bump Bump) -> Result<Self, Error> {
+        let input = crate::io::read_to_bump_str(reader, bump)?;
+        let raw = serde_json::from_str(input).map_err(|error| Error::from_input(input, error))?;
+        Self::from_raw_value(raw, bump)
+    }
+
+    /// Constructs a map from the JSON object read from a gzip-compressed `reader`.
+    ///
+    /// The bytes are decompressed directly into the bump allocator, see [`Self::from_reader`].
+    ///
+    /// # Errors
+    ///
+    /// - if reading or decompressing fails, or the decompressed bytes are not valid UTF-8.
+    /// - if the decompressed data cannot be parsed as a map (JSON object).
+    #[cfg(feature = "flate2")]
+    pub fn from_gzip_reader<R: std::io::Read>(reader: R, bump: &'bump Bump) -> Result<Self, Error> {
+        Self::from_reader(flate2::read::MultiGzDecoder::new(reader), bump)
+    }
+
+    /// Constructs a map from the JSON object read from a zstd-compressed `reader`.
+    ///
+    /// The bytes are decompressed directly into the bump allocator, see [`Self::from_reader`].
+    ///
+    /// # Errors
+    ///
+    /// - if reading or decompressing fails, or the decompressed bytes are not valid UTF-8.
+    /// - if the decompressed data cannot be parsed as a map (JSON object).
+    #[cfg(feature = "zstd")]
+    pub fn from_zstd_reader<R: std::io::Read>(reader: R, bump: &'bump Bump) -> Result<Self, Error> {
+        Self::from_reader(zstd::Decoder::new(reader)?, bump)
+    }
+
+    /// Constructs a map from JSON bytes that may contain invalid UTF-8 sequences.
+    ///
+    /// The bytes are decoded in the bump with [`crate::io::decode_lossy_in`]: a leading byte order mark
+    /// is skipped and the invalid sequences are replaced by U+FFFD (`�`) instead of rejecting the input.
+    /// Returns the map along with the keys of the entries whose key or value contained invalid sequences.
+    ///
+    /// # Errors
+    ///
+    /// - if the decoded data cannot be parsed as a map (JSON object).
+    pub fn from_slice_lossy(
+        input: &[u8],
+        bump: &'bump Bump,
+    ) -> Result<(Self, Vec<&'bump str>), Error> {
+        let (input, replaced) = crate::io::decode_lossy_in(input, bump);
+        let raw = serde_json::from_str(input).map_err(|error| Error::from_input(input, error))?;
+        let map = Self::from_raw_value(raw, bump)?;
+        let affected = if replaced.is_empty() {
+            Vec::new()
+        } else {
+            map.iter()
+                .filter(|(_, value)| {
+                    scan::entry_span(value, input).is_some_and(|span| {
+                        replaced.iter().any(|replacement| {
+                            span.key.contains(&replacement.start)
+                                || span.value.contains(&replacement.start)
+                        })
+                    })
+                })
+                .map(|(key, _)| key)
+                .collect()
+        };
+        Ok((map, affected))
+    }
+
+    /// Constructs one map per element of a raw value holding a JSON array of objects.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a sequence (JSON array).
+    /// - if an element cannot be parsed as a map (JSON object), reporting the index of the element in the path.
+    pub fn parse_many(raw: &'bump RawValue, bump: &'bump Bump) -> Result<BVec<'bump, Self>, Error> {
+        let array = RawVec::from_raw_value(raw, bump)?;
+        let mut maps = BVec::with_capacity_in(array.len(), bump);
+        for (index, element) in array.into_iter().enumerate() {
+            let map = Self::from_raw_value(element, bump)
+                .map_err(|error| error.with_path(|| format!("[{index}]")))?;
+            maps.push(map);
+        }
+        Ok(maps)
+    }
+
+    /// Constructs a map from a JSON object, collecting the errors of all the malformed values.
+    ///
+    /// Unlike [`Self::from_raw_value`], parsing does not stop at the first malformed value:
+    /// the entries with a valid value are inserted in the returned map and the error of each other entry
+    /// is reported along with its key.
+    ///
+    /// # Errors
+    ///
+    /// - if the input does not start with a JSON object, or if the structure of the object
+    ///   is too broken to find the next entry.
+    pub fn try_from_str_all_errors(
+        input: &'bump str,
+        bump: &'bump Bump,
+    ) -> Result<(Self, Vec<KeyError<'bump>>), Error> {
+        let mut map = Self::new_in(bump);
+        let mut errors = Vec::new();
+        let mut scanner = scan::ObjectScanner::new(input)?;
+        while let Some(span) = scanner.next_entry()? {
+            let raw_key = &input[span.key.clone()];
+            let key = match BumpStrSeed(bump)
+                .deserialize(&mut serde_json::Deserializer::from_str(raw_key))
+            {
+                Ok(key) => key,
+                Err(error) => {
+                    errors.push(KeyError {
+                        key: raw_key.trim_matches('"'),
+                        error,
+                        span,
+                    });
+                    continue;
+                }
+            };
+            match serde_json::from_str(&input[span.value.clone()]) {
+                Ok(value) => {
+                    map.insert(key, value);
+                }
+                Err(error) => errors.push(KeyError { key, error, span }),
+            }
+        }
+        Ok((map, errors))
     }
 
     /// Constructs an empty map backed by the specified bump allocator.
@@ -67,6 +325,12 @@ impl<'bump> RawMap<'bump, DefaultHashBuilder> {
             cache: hashbrown::HashMap::new_in(bump),
         }
     }
+
+    /// Constructs an empty map with room for at least `capacity` elements, backed by the specified bump allocator.
+    #[inline]
+    pub fn with_capacity_in(capacity: usize, bump: &'bump Bump) -> Self {
+        Self::with_capacity_and_hasher_in(capacity, DefaultHashBuilder::default(), bump)
+    }
 }
 
 impl<'bump, S: BuildHasher> RawMap<'bump, S> {
@@ -80,8 +344,9 @@ impl<'bump, S: BuildHasher> RawMap<'bump, S> {
         raw: &'bump RawValue,
         hash_builder: S,
         bump: &'bump Bump,
-    ) -> Result<Self, serde_json::Error> {
+    ) -> Result<Self, Error> {
         Self::from_deserializer_and_hasher(raw, hash_builder, bump)
+            .map_err(|error| Error::from_raw_value(raw, Kind::Object, error))
     }
 
     /// Inserts a new (key, value) pair in the map.
@@ -107,6 +372,62 @@ impl<'bump, S: BuildHasher> RawMap<'bump, S> {
         }
     }
 
+    /// Inserts a new (key, value) pair in the map, copying the key and the value into the bump.
+    ///
+    /// The key is only copied if it is not already present in the map.
+    /// Apart from that, this behaves like [`Self::insert`].
+    #[inline]
+    pub fn insert_copied(&mut self, key: &str, value: &RawValue) -> Option<&'bump RawValue> {
+        let value = crate::value::copy_raw_value_in(value, self.bump());
+        match self.entry_ref(key) {
+            EntryRef::Occupied(mut entry) => Some(entry.insert(value)),
+            EntryRef::Vacant(entry) => {
+                entry.insert(value);
+                None
+            }
+        }
+    }
+
+    /// Appends a new (key, value) pair at the end of the map, even if the key already exists.
+    ///
+    /// When a key is pushed multiple times, all its occurrences are kept, iterated over and serialized,
+    /// and the lookups return the last occurrence. [`Self::insert`] then updates the value of the last occurrence.
+    ///
+    /// The methods that reorder the entries only keep the lookups pointing at the last occurrence
+    /// of the keys that are not duplicated.
+    #[inline]
+    pub fn push(&mut self, key: &'bump str, value: &'bump RawValue) {
+        let index = self.data.len();
+        self.data.push((key, value));
+        self.cache.insert(key, index);
+    }
+
+    /// Removes the entry associated with a key by replacing it with the last entry, and returns its value.
+    ///
+    /// This is an O(1) removal that does not preserve the order of the entries:
+    /// the last entry takes the position of the removed one.
+    /// Only the occurrence of the key that lookups return is removed.
+    #[inline]
+    pub fn swap_remove(&mut self, key: &str) -> Option<&'bump RawValue> {
+        let index = self.cache.remove(key)?;
+        let (_, value) = self.data.swap_remove(index);
+        let last = self.data.len();
+        if let Some(&(moved, _)) = self.data.get(index) {
+            if let Some(moved_index) = self.cache.get_mut(moved).filter(|i| **i == last) {
+                *moved_index = index;
+            }
+        }
+        Some(value)
+    }
+
+    /// `true` if the map contains multiple occurrences of the same key.
+    ///
+    /// This can only happen after calls to [`Self::push`].
+    #[inline]
+    pub fn has_duplicates(&self) -> bool {
+        self.cache.len() != self.data.len()
+    }
+
     /// Retrieves the value associated with a key, if present.
     #[inline]
     pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
@@ -120,6 +441,82 @@ impl<'bump, S: BuildHasher> RawMap<'bump, S> {
         self.cache.get(key).copied()
     }
 
+    /// Returns the byte length of the raw JSON value associated with a key, if present.
+    ///
+    /// The length of a string value includes its quotes and escape sequences.
+    #[inline]
+    pub fn value_len(&self, key: &str) -> Option<usize> {
+        self.get(key).map(|value| value.get().len())
+    }
+
+    /// Truncates the string value associated with a key so that its raw JSON is at most `max_bytes` long.
+    ///
+    /// The string is cut between characters and escape sequences, so the truncated value stays
+    /// correctly escaped, and is allocated in the bump. The value is never shorter than an empty string (`""`).
+    /// Returns `true` if the value was truncated, and `false` if the key is absent or the value is short enough.
+    ///
+    /// # Errors
+    ///
+    /// - if the value is not a string. The path of the error is the key.
+    pub fn truncate_value(&mut self, key: &str, max_bytes: usize) -> Result<bool, Error> {
+        let Some(index) = self.get_index(key) else {
+            return Ok(false);
+        };
+        let (key, value) = self.data[index];
+        let found = Kind::of(value);
+        if found != Kind::String {
+            return Err(Error::WrongKind {
+                path: Some(key.to_owned()),
+                expected: Kind::String,
+                found,
+            });
+        }
+        let json = value.get();
+        if json.len() <= max_bytes {
+            return Ok(false);
+        }
+
+        // keep the characters and escape sequences that fit between the quotes
+        let content = &json[1..json.len() - 1];
+        let budget = max_bytes.saturating_sub(2);
+        let mut end = 0;
+        while end < content.len() {
+            let next = end + string_token_len(&content[end..]);
+            if next > budget {
+                break;
+            }
+            end = next;
+        }
+        let truncated = bumpalo::format!(in self.bump(), "\"{}\"", &content[..end]);
+        // the content is cut at a token boundary of a valid JSON string
+        self.data[index].1 = serde_json::from_str(truncated.into_bump_str()).unwrap();
+        Ok(true)
+    }
+
+    /// Retrieves the location of the entry associated with a key in `source`, if present.
+    ///
+    /// `source` must be the JSON object the map was parsed from. Returns `None` if the key is absent,
+    /// or if its value does not come from `source`, for example if it was inserted after parsing.
+    #[inline]
+    pub fn get_span(&self, key: &str, source: &str) -> Option<EntrySpan> {
+        scan::entry_span(self.get(key)?, source)
+    }
+
+    /// Retrieves the value associated with a key and deserializes it, if present.
+    ///
+    /// # Errors
+    ///
+    /// - if the value cannot be deserialized as a `T`, as [`Error::Deserialize`]. The path of the error is the key.
+    #[inline]
+    pub fn get_deserialize<T>(&self, key: &str) -> Result<Option<T>, Error>
+    where
+        T: Deserialize<'bump>,
+    {
+        self.get(key)
+            .map(|value| deserialize_value(key, value))
+            .transpose()
+    }
+
     /// Reserves capacity for at least additional more elements to be inserted in the map.
     ///
     /// # Panics
@@ -130,6 +527,190 @@ impl<'bump, S: BuildHasher> RawMap<'bump, S> {
         self.data.reserve(additional);
         self.cache.reserve(additional);
     }
+
+    /// Builds a new map in the same bump containing only the requested keys.
+    ///
+    /// The entries of the new map keep their relative order in this map, regardless of the order of `keys`.
+    /// The requested keys that are absent from this map are returned alongside the new map.
+    pub fn project<'k, I>(&self, keys: I) -> (RawMap<'bump, S>, Vec<&'k str>)
+    where
+        I: IntoIterator<Item = &'k str>,
+        S: Clone,
+    {
+        let mut missing = Vec::new();
+        let mut indices: Vec<usize> = keys
+            .into_iter()
+            .filter_map(|key| {
+                let index = self.get_index(key);
+                if index.is_none() {
+                    missing.push(key);
+                }
+                index
+            })
+            .collect();
+        indices.sort_unstable();
+        indices.dedup();
+
+        let mut projection = RawMap::with_hasher_in(self.cache.hasher().clone(), self.bump());
+        projection.reserve(indices.len());
+        for index in indices {
+            let (key, value) = self.data[index];
+            projection.insert(key, value);
+        }
+        (projection, missing)
+    }
+
+    /// Copies the live entries of the map into a new map allocated in `bump`.
+    ///
+    /// Only the keys and values that are reachable from the map are copied,
+    /// leaving behind the allocations of overwritten values or removed entries.
+    pub fn compact_into<'new>(&self, bump: &'new Bump) -> RawMap<'new, S>
+    where
+        S: Clone,
+    {
+        let mut compacted = RawMap::with_hasher_in(self.cache.hasher().clone(), bump);
+        compacted.reserve(self.len());
+        for &(key, value) in self.data.iter() {
+            let key = bump.alloc_str(key);
+            let value = crate::value::copy_raw_value_in(value, bump);
+            compacted.push(key, value);
+        }
+        compacted
+    }
+
+    /// Retains only the entries whose key satisfies the predicate, preserving their order.
+    ///
+    /// The entries are compacted in a single pass, and the lookup cache is updated in place.
+    /// The predicate is called once per entry, in order. The occurrences of a duplicated key are
+    /// retained or removed independently, and the lookups then return the last retained occurrence.
+    pub fn retain_keys<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&str) -> bool,
+    {
+        self.retain_entries(|_, key, _| keep(key));
+    }
+
+    /// Retains only the entries that satisfy the predicate, which also receives their position.
+    ///
+    /// See [`Self::retain_keys`].
+    pub(crate) fn retain_entries<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(usize, &'bump str, &'bump RawValue) -> bool,
+    {
+        // with duplicates, the lookup of a retained occurrence must be updated even if it did not move
+        let duplicates = self.has_duplicates();
+        let mut write = 0;
+        for read in 0..self.data.len() {
+            let entry = self.data[read];
+            if keep(read, entry.0, entry.1) {
+                if write != read || duplicates {
+                    self.data[write] = entry;
+                    self.cache.insert(entry.0, write);
+                }
+                write += 1;
+            } else if self.cache.get(entry.0) == Some(&read) {
+                // the lookups of the previous occurrences retained were moved before `write`,
+                // so they never point at `read`
+                self.cache.remove(entry.0);
+            }
+        }
+        self.data.truncate(write);
+    }
+
+    /// Builds a new map in the same bump, whose values are the result of `f` on the entries of this map.
+    ///
+    /// The entries keep their order, and the keys are shared with this map instead of being copied.
+    /// The lookup cache is cloned rather than rebuilt, so that no key is hashed again.
+    pub fn map_values<F>(&self, mut f: F) -> Self
+    where
+        F: FnMut(&'bump str, &'bump RawValue) -> &'bump RawValue,
+        S: Clone,
+    {
+        let bump = self.bump();
+        let mut data = BVec::with_capacity_in(self.data.len(), bump);
+        data.extend(self.data.iter().map(|&(key, value)| (key, f(key, value))));
+        Self {
+            data,
+            cache: self.cache.clone(),
+        }
+    }
+
+    /// Builds a new map in the same bump, whose values are the result of `f` on the entries of this map.
+    ///
+    /// See [`Self::map_values`].
+    ///
+    /// # Errors
+    ///
+    /// - if `f` fails on an entry, reported as [`Error::Rejected`] with the key of the entry.
+    pub fn try_map_values<F, E>(&self, mut f: F) -> Result<Self, Error>
+    where
+        F: FnMut(&'bump str, &'bump RawValue) -> Result<&'bump RawValue, E>,
+        E: Into<Box<dyn std::error::Error + Send + Sync>>,
+        S: Clone,
+    {
+        let bump = self.bump();
+        let mut data = BVec::with_capacity_in(self.data.len(), bump);
+        for &(key, value) in &self.data {
+            let value = f(key, value).map_err(|reason| Error::Rejected {
+                key: key.to_string(),
+                reason: reason.into(),
+            })?;
+            data.push((key, value));
+        }
+        Ok(Self {
+            data,
+            cache: self.cache.clone(),
+        })
+    }
+
+    /// Moves the entry at index `from` to index `to`, shifting the entries in between.
+    ///
+    /// After the move, the entry is iterated at position `to`.
+    ///
+    /// # Panics
+    ///
+    /// - if `from` or `to` are out of bounds.
+    #[inline]
+    pub fn move_index(&mut self, from: usize, to: usize) {
+        let entry = self.data.remove(from);
+        self.data.insert(to, entry);
+        self.update_cache_indices(from.min(to)..from.max(to) + 1);
+    }
+
+    /// Swaps the position of the entries at indices `a` and `b`.
+    ///
+    /// # Panics
+    ///
+    /// - if `a` or `b` are out of bounds.
+    #[inline]
+    pub fn swap_indices(&mut self, a: usize, b: usize) {
+        self.data.swap(a, b);
+        self.update_cache_indices(a..a + 1);
+        self.update_cache_indices(b..b + 1);
+    }
+
+    /// Sorts the entries of the map by comparing their values with `compare`.
+    ///
+    /// The sort is stable: entries with equal values keep their relative order.
+    ///
+    /// See [`crate::value::cmp_number_or_string`] for a comparator that orders numbers and strings.
+    #[inline]
+    pub fn sort_by_value<F>(&mut self, mut compare: F)
+    where
+        F: FnMut(&RawValue, &RawValue) -> Ordering,
+    {
+        self.data
+            .sort_by(|(_, left), (_, right)| compare(left, right));
+        self.update_cache_indices(0..self.data.len());
+    }
+
+    /// Points the cache entries of the keys in `range` back to their index in the data vector.
+    fn update_cache_indices(&mut self, range: std::ops::Range<usize>) {
+        for index in range {
+            let (key, _) = self.data[index];
+            *self.cache.get_mut(key).unwrap() = index;
+        }
+    }
 }
 
 impl<'bump, S> RawMap<'bump, S> {
@@ -142,6 +723,19 @@ impl<'bump, S> RawMap<'bump, S> {
         }
     }
 
+    /// Constructs an empty map with room for at least `capacity` elements, backed by the specified bump allocator.
+    #[inline]
+    pub fn with_capacity_and_hasher_in(
+        capacity: usize,
+        hash_builder: S,
+        bump: &'bump Bump,
+    ) -> Self {
+        Self {
+            data: BVec::with_capacity_in(capacity, bump),
+            cache: hashbrown::HashMap::with_capacity_and_hasher_in(capacity, hash_builder, bump),
+        }
+    }
+
     /// The number of elements in the map.
     #[inline]
     pub fn len(&self) -> usize {
@@ -183,6 +777,40 @@ impl<'bump, S> RawMap<'bump, S> {
     pub fn bump(&self) -> &'bump Bump {
         self.data.bump()
     }
+
+    /// Serializes the whole map as a JSON object allocated in `bump`.
+    ///
+    /// The returned raw value can be inserted in another map, for example to reconstruct a nested document.
+    /// The keys and the separators are written in compact form, the values are written as-is.
+    pub fn to_raw_value_in<'b>(&self, bump: &'b Bump) -> &'b RawValue {
+        // string keys and raw values are always serializable
+        crate::value::to_raw_value_in(self, bump).unwrap()
+    }
+}
+
+impl<'a, 'bump, S: Send> Freeze for &'a mut RawMap<'bump, S> {
+    type Frozen = FrozenRawMap<'a, 'bump, S>;
+
+    #[inline]
+    fn freeze(self) -> Self::Frozen {
+        FrozenRawMap::new(self)
+    }
+}
+
+impl<'bump, S> Deref for RawMap<'bump, S> {
+    type Target = [(&'bump str, &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<'bump, S> AsRef<[(&'bump str, &'bump RawValue)]> for RawMap<'bump, S> {
+    #[inline]
+    fn as_ref(&self) -> &[(&'bump str, &'bump RawValue)] {
+        self.as_slice()
+    }
 }
 
 impl<S> fmt::Debug for RawMap<'_, S> {
@@ -191,10 +819,53 @@ impl<S> fmt::Debug for RawMap<'_, S> {
     }
 }
 
+fn deserialize_value<'bump, T>(key: &str, value: &'bump RawValue) -> Result<T, Error>
+where
+    T: Deserialize<'bump>,
+{
+    T::deserialize(value).map_err(|error| Error::from(error).with_path(|| key.to_owned()))
+}
+
+/// Returns the byte length of the character or escape sequence at the start of the content of a JSON string.
+///
+/// A surrogate pair is a single sequence, so that it is never split.
+fn string_token_len(content: &str) -> usize {
+    let bytes = content.as_bytes();
+    match bytes {
+        [b'\\', b'u', high @ ..] => {
+            let high = std::str::from_utf8(&high[..4])
+                .ok()
+                .and_then(|high| u16::from_str_radix(high, 16).ok());
+            let is_high_surrogate = high.is_some_and(|high| (0xD800..0xDC00).contains(&high));
+            if is_high_surrogate && bytes[6..].starts_with(b"\\u") {
+                12
+            } else {
+                6
+            }
+        }
+        [b'\\', ..] => 2,
+        _ => content.chars().next().map_or(0, char::len_utf8),
+    }
+}
+
+/// The error of a single entry, as reported by [`RawMap::try_from_str_all_errors`].
+#[derive(Debug)]
+pub struct KeyError<'bump> {
+    /// The key of the malformed entry.
+    pub key: &'bump str,
+    /// The parsing error.
+    pub error: serde_json::Error,
+    /// The location of the malformed entry in the input.
+    pub span: EntrySpan,
+}
+
 /// A view into a [`RawMap`] that prevents insertions, but can be sent between threads safely.
 pub struct FrozenRawMap<'a, 'bump, S> {
     data: &'a [(&'bump str, &'bump RawValue)],
-    cache: frozen::FrozenMap<'a, 'bump, &'bump str, usize, S>,
+    /// `None` if the entries are sorted by key, in which case lookups use a binary search.
+    cache: Option<frozen::FrozenMap<'a, 'bump, &'bump str, usize, S>>,
+    /// The index built by [`Self::optimize`], used instead of the cache when present.
+    index: Option<optimize::FingerprintIndex>,
 }
 
 impl<'a, 'bump, S> FrozenRawMap<'a, 'bump, S> {
@@ -203,7 +874,46 @@ impl<'a, 'bump, S> FrozenRawMap<'a, 'bump, S> {
     pub fn new(map: &'a mut RawMap<'bump, S>) -> Self {
         FrozenRawMap {
             data: map.data.as_slice(),
-            cache: frozen::FrozenMap::new(&mut map.cache),
+            cache: Some(frozen::FrozenMap::new(&mut map.cache)),
+            index: None,
+        }
+    }
+}
+
+impl<'a, 'bump> FrozenRawMap<'a, 'bump, DefaultHashBuilder> {
+    /// Constructs a view over entries sorted by key, without building a hash table.
+    ///
+    /// The lookups are binary searches over the entries, that keep their order.
+    ///
+    /// # Errors
+    ///
+    /// - if a key is repeated, as [`Error::DuplicateKey`].
+    /// - if the entries are not sorted by key, as [`Error::UnsortedKeys`].
+    pub fn from_sorted_slice(entries: &'a [(&'bump str, &'bump RawValue)]) -> Result<Self, Error> {
+        for (index, pair) in entries.windows(2).enumerate() {
+            match pair[0].0.cmp(pair[1].0) {
+                Ordering::Less => (),
+                Ordering::Equal => {
+                    return Err(Error::DuplicateKey {
+                        key: pair[1].0.to_string(),
+                    })
+                }
+                Ordering::Greater => return Err(Error::UnsortedKeys { index: index + 1 }),
+            }
+        }
+        Ok(Self::from_sorted_slice_unchecked(entries))
+    }
+
+    /// Constructs a view over entries sorted by key, without checking that they are sorted.
+    ///
+    /// See [`Self::from_sorted_slice`]. The lookups may miss keys if the entries are not sorted,
+    /// and may return any of the entries of a repeated key.
+    #[inline]
+    pub fn from_sorted_slice_unchecked(entries: &'a [(&'bump str, &'bump RawValue)]) -> Self {
+        FrozenRawMap {
+            data: entries,
+            cache: None,
+            index: None,
         }
     }
 }
@@ -212,14 +922,53 @@ impl<'bump, S: BuildHasher> FrozenRawMap<'_, 'bump, S> {
     /// Retrieves the value associated with a key, if present.
     #[inline]
     pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
-        let index = self.cache.get(key)?;
-        self.data.get(*index).map(|(_, v)| *v)
+        let index = self.get_index(key)?;
+        self.data.get(index).map(|(_, v)| *v)
     }
 
     /// Retrieves the index of a key in the data slice, if present.
     #[inline]
     pub fn get_index(&self, key: &str) -> Option<usize> {
-        self.cache.get(key).copied()
+        if let Some(index) = &self.index {
+            return index.get(key, self.data);
+        }
+        match &self.cache {
+            Some(cache) => cache.get(key).copied(),
+            None => self.data.binary_search_by(|(k, _)| (*k).cmp(key)).ok(),
+        }
+    }
+
+    /// Retrieves the location of the entry associated with a key in `source`, if present.
+    ///
+    /// `source` must be the JSON object the map was parsed from. Returns `None` if the key is absent,
+    /// or if its value does not come from `source`, for example if it was inserted after parsing.
+    #[inline]
+    pub fn get_span(&self, key: &str, source: &str) -> Option<EntrySpan> {
+        scan::entry_span(self.get(key)?, source)
+    }
+
+    /// Retrieves the value associated with a key and deserializes it, if present.
+    ///
+    /// # Errors
+    ///
+    /// - if the value cannot be deserialized as a `T`, as [`Error::Deserialize`]. The path of the error is the key.
+    #[inline]
+    pub fn get_deserialize<T>(&self, key: &str) -> Result<Option<T>, Error>
+    where
+        T: Deserialize<'bump>,
+    {
+        self.get(key)
+            .map(|value| deserialize_value(key, value))
+            .transpose()
+    }
+
+    /// Retrieves the key and value associated with a key, if present.
+    ///
+    /// The returned key is the one allocated in the bump when the entry was first inserted.
+    #[inline]
+    pub fn get_key_value(&self, key: &str) -> Option<(&'bump str, &'bump RawValue)> {
+        let index = self.get_index(key)?;
+        self.data.get(index).copied()
     }
 }
 
@@ -243,6 +992,22 @@ impl<'a, 'bump, S> FrozenRawMap<'a, 'bump, S> {
     }
 }
 
+impl<'bump, S> Deref for FrozenRawMap<'_, 'bump, S> {
+    type Target = [(&'bump str, &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.data
+    }
+}
+
+impl<'bump, S> AsRef<[(&'bump str, &'bump RawValue)]> for FrozenRawMap<'_, 'bump, S> {
+    #[inline]
+    fn as_ref(&self) -> &[(&'bump str, &'bump RawValue)] {
+        self.data
+    }
+}
+
 impl<S> fmt::Debug for FrozenRawMap<'_, '_, S> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         f.debug_struct("FrozenRawMap")
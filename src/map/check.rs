@@ -0,0 +1,111 @@
+use std::collections::BTreeMap;
+use std::hash::BuildHasher;
+
+use bumpalo::Bump;
+use serde_json::value::RawValue;
+
+use super::FrozenRawMap;
+use crate::value::Kind;
+use crate::{Error, RawMap};
+
+impl<S: BuildHasher> RawMap<'_, S> {
+    /// Checks the kinds of the values at the paths of `schema`, returning all the violations.
+    ///
+    /// The paths are made of keys separated by dots. Each violation is an [`Error::WrongKind`]
+    /// holding the path of the value. A path that is absent from the document is not a violation,
+    /// but a path that goes through a value that is not an object is. The violations are ordered by path.
+    ///
+    /// The kinds are sniffed from the first byte of the values, and the nested objects on the paths
+    /// are parsed once, whatever the number of paths that go through them.
+    pub fn check_types(&self, schema: &[(&str, Kind)]) -> Vec<Error> {
+        check_types(|key| self.get(key), schema)
+    }
+}
+
+impl<S: BuildHasher> FrozenRawMap<'_, '_, S> {
+    /// Checks the kinds of the values at the paths of `schema`, returning all the violations.
+    ///
+    /// See [`RawMap::check_types`].
+    pub fn check_types(&self, schema: &[(&str, Kind)]) -> Vec<Error> {
+        check_types(|key| self.get(key), schema)
+    }
+}
+
+/// A path of the schema, with the part that remains to be resolved.
+struct Expectation<'s> {
+    path: &'s str,
+    rest: &'s str,
+    kind: Kind,
+}
+
+fn check_types<'a>(
+    get: impl Fn(&str) -> Option<&'a RawValue>,
+    schema: &[(&str, Kind)],
+) -> Vec<Error> {
+    let bump = Bump::new();
+    let mut violations = Vec::new();
+    let expectations = schema
+        .iter()
+        .map(|&(path, kind)| Expectation {
+            path,
+            rest: path,
+            kind,
+        })
+        .collect();
+    check_object(&get, expectations, &bump, &mut violations);
+    violations
+}
+
+fn check_object<'a>(
+    get: &dyn Fn(&str) -> Option<&'a RawValue>,
+    expectations: Vec<Expectation<'_>>,
+    bump: &Bump,
+    violations: &mut Vec<Error>,
+) {
+    // group the expectations by key, to look up and parse each nested object once
+    let mut by_key: BTreeMap<&str, Vec<Expectation>> = BTreeMap::new();
+    for expectation in expectations {
+        let key = expectation
+            .rest
+            .split_once('.')
+            .map_or(expectation.rest, |(key, _)| key);
+        by_key.entry(key).or_default().push(expectation);
+    }
+
+    for (key, expectations) in by_key {
+        let Some(value) = get(key) else {
+            continue;
+        };
+        let found = Kind::of(value);
+        let mut nested = Vec::new();
+        for expectation in expectations {
+            match expectation.rest.split_once('.') {
+                None if found != expectation.kind => violations.push(Error::WrongKind {
+                    path: Some(expectation.path.to_owned()),
+                    expected: expectation.kind,
+                    found,
+                }),
+                None => (),
+                Some((_, rest)) => nested.push(Expectation {
+                    rest,
+                    ..expectation
+                }),
+            }
+        }
+        let Some(first) = nested.first() else {
+            continue;
+        };
+        if found != Kind::Object {
+            let prefix_len = first.path.len() - first.rest.len() - 1;
+            violations.push(Error::WrongKind {
+                path: Some(first.path[..prefix_len].to_owned()),
+                expected: Kind::Object,
+                found,
+            });
+            continue;
+        }
+        // raw values of the object kind are always valid objects
+        let map = RawMap::from_raw_value(value, bump).unwrap();
+        check_object(&|key| map.get(key), nested, bump, violations);
+    }
+}
@@ -0,0 +1,119 @@
+use std::hash::BuildHasher;
+use std::ops::Deref;
+
+use serde_json::value::RawValue;
+
+use crate::RawMap;
+
+/// A speculative modification of a [`RawMap`], that can be rolled back.
+///
+/// This is constructed by [`RawMap::checkpoint`]. The insertions made through the checkpoint
+/// are applied to the map immediately, and the checkpoint records what is needed to undo them.
+/// The map is left as is when the checkpoint is committed or dropped.
+///
+/// Note that the memory allocated in the bump since the checkpoint cannot be reclaimed on rollback,
+/// but [`Self::allocated_bytes`] reports it.
+pub struct Checkpoint<'a, 'bump, S> {
+    map: &'a mut RawMap<'bump, S>,
+    len: usize,
+    allocated_bytes: usize,
+    undo: Vec<Undo<'bump>>,
+}
+
+enum Undo<'bump> {
+    /// The value at `index` was overwritten.
+    Value {
+        index: usize,
+        previous: &'bump RawValue,
+    },
+    /// The lookup of a duplicated `key` was pointing at `previous`.
+    Index { key: &'bump str, previous: usize },
+}
+
+impl<'bump, S: BuildHasher> RawMap<'bump, S> {
+    /// Starts recording the insertions in the map, so that they can be rolled back.
+    #[inline]
+    pub fn checkpoint(&mut self) -> Checkpoint<'_, 'bump, S> {
+        Checkpoint {
+            len: self.len(),
+            allocated_bytes: self.bump().allocated_bytes(),
+            map: self,
+            undo: Vec::new(),
+        }
+    }
+}
+
+impl<'bump, S: BuildHasher> Checkpoint<'_, 'bump, S> {
+    /// Inserts a new (key, value) pair in the map, as [`RawMap::insert`] does.
+    #[inline]
+    pub fn insert(&mut self, key: &'bump str, value: &'bump RawValue) -> Option<&'bump RawValue> {
+        let index = self.map.get_index(key);
+        let previous = self.map.insert(key, value);
+        if let (Some(index), Some(previous)) = (index, previous) {
+            self.undo.push(Undo::Value { index, previous });
+        }
+        previous
+    }
+
+    /// Appends a new (key, value) pair at the end of the map, as [`RawMap::push`] does.
+    #[inline]
+    pub fn push(&mut self, key: &'bump str, value: &'bump RawValue) {
+        // an entry pushed since the checkpoint is truncated on rollback, so its lookup is not restored
+        if let Some(previous) = self.map.get_index(key).filter(|&index| index < self.len) {
+            self.undo.push(Undo::Index { key, previous });
+        }
+        self.map.push(key, value);
+    }
+
+    /// Inserts a new (key, value) pair in the map, as [`RawMap::insert_copied`] does.
+    #[inline]
+    pub fn insert_copied(&mut self, key: &str, value: &RawValue) -> Option<&'bump RawValue> {
+        let index = self.map.get_index(key);
+        let previous = self.map.insert_copied(key, value);
+        if let (Some(index), Some(previous)) = (index, previous) {
+            self.undo.push(Undo::Value { index, previous });
+        }
+        previous
+    }
+
+    /// The number of bytes allocated in the bump of the map since the checkpoint.
+    #[inline]
+    pub fn allocated_bytes(&self) -> usize {
+        self.map
+            .bump()
+            .allocated_bytes()
+            .saturating_sub(self.allocated_bytes)
+    }
+
+    /// Keeps the insertions made since the checkpoint.
+    #[inline]
+    pub fn commit(self) {}
+
+    /// Undoes the insertions made since the checkpoint.
+    pub fn rollback(self) {
+        let map = self.map;
+        for &(key, _) in map.data[self.len..].iter().rev() {
+            if map.cache.get(key).is_some_and(|&index| index >= self.len) {
+                map.cache.remove(key);
+            }
+        }
+        for undo in self.undo.into_iter().rev() {
+            match undo {
+                Undo::Value { index, previous } => map.data[index].1 = previous,
+                Undo::Index { key, previous } => {
+                    map.cache.insert(key, previous);
+                }
+            }
+        }
+        map.data.truncate(self.len);
+    }
+}
+
+impl<'bump, S> Deref for Checkpoint<'_, 'bump, S> {
+    type Target = RawMap<'bump, S>;
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.map
+    }
+}
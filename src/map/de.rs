@@ -1,18 +1,30 @@
 use std::hash::BuildHasher;
 
+use bumpalo::collections::Vec as BVec;
 use bumpalo::Bump;
 use hashbrown::DefaultHashBuilder;
-use serde::{de::Visitor, Deserializer};
+use serde::de::{Error as _, Visitor};
+use serde::Deserializer;
 use serde_json::value::RawValue;
 
-use crate::{de::BumpStrSeed, RawMap};
+use crate::de::{BumpStrSeed, InternedStrSeed};
+use crate::interner::Interner;
+use crate::map::normalized::{normalize_in, KeyNormalizer};
+use crate::RawMap;
 
-pub struct BumpRawMapVisitor<'bump, S> {
+/// A callback rejecting an entry by returning an error.
+pub(crate) type Validate<'a> = &'a mut dyn FnMut(&str, &RawValue) -> Result<(), ()>;
+
+pub struct BumpRawMapVisitor<'a, 'bump, S> {
     bump: &'bump Bump,
     hash_builder: S,
+    interner: Option<&'a mut Interner<'bump>>,
+    preserve_duplicates: bool,
+    validate: Option<Validate<'a>>,
+    normalizer: Option<&'a dyn KeyNormalizer>,
 }
 
-impl<'bump, S: BuildHasher> Visitor<'bump> for BumpRawMapVisitor<'bump, S> {
+impl<'bump, S: BuildHasher> Visitor<'bump> for BumpRawMapVisitor<'_, 'bump, S> {
     type Value = RawMap<'bump, S>;
 
     fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
@@ -29,9 +41,28 @@ impl<'bump, S: BuildHasher> Visitor<'bump> for BumpRawMapVisitor<'bump, S> {
         if let Some(size_hint) = map.size_hint() {
             top.reserve(size_hint);
         }
-        while let Some(key) = map.next_key_seed(BumpStrSeed(self.bump))? {
+        let mut interner = self.interner;
+        let mut validate = self.validate;
+        loop {
+            let key = match interner.as_deref_mut() {
+                Some(interner) => map.next_key_seed(InternedStrSeed(interner))?,
+                None => map.next_key_seed(BumpStrSeed(self.bump))?,
+            };
+            let Some(mut key) = key else { break };
+            if let Some(normalizer) = self.normalizer {
+                key = normalize_in(normalizer, key, self.bump);
+            }
             let value: &'bump RawValue = map.next_value()?;
-            top.insert(key, value);
+            if let Some(validate) = validate.as_deref_mut() {
+                if validate(key, value).is_err() {
+                    return Err(A::Error::custom(format!("the `{key}` entry was rejected")));
+                }
+            }
+            if self.preserve_duplicates {
+                top.push(key, value);
+            } else {
+                top.insert(key, value);
+            }
         }
         Ok(top)
     }
@@ -45,6 +76,31 @@ impl<'bump, S: BuildHasher> Visitor<'bump> for BumpRawMapVisitor<'bump, S> {
     }
 }
 
+/// Deserializes the entries of a map in a bump-allocated vector, without indexing the keys.
+///
+/// Unlike in a [`RawMap`], all the occurrences of duplicated keys are kept, in their original order.
+pub(crate) struct BumpEntriesVisitor<'bump>(pub(crate) &'bump Bump);
+
+impl<'bump> Visitor<'bump> for BumpEntriesVisitor<'bump> {
+    type Value = BVec<'bump, (&'bump str, &'bump RawValue)>;
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter) -> std::fmt::Result {
+        write!(formatter, "a map")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'bump>,
+    {
+        let mut entries = BVec::with_capacity_in(map.size_hint().unwrap_or(0), self.0);
+        while let Some(key) = map.next_key_seed(BumpStrSeed(self.0))? {
+            let value: &'bump RawValue = map.next_value()?;
+            entries.push((key, value));
+        }
+        Ok(entries)
+    }
+}
+
 impl<'bump> RawMap<'bump> {
     /// Constructs a new map from a [`Deserializer`].
     ///
@@ -59,11 +115,116 @@ impl<'bump> RawMap<'bump> {
         deserializer.deserialize_map(BumpRawMapVisitor {
             bump,
             hash_builder: DefaultHashBuilder::default(),
+            interner: None,
+            preserve_duplicates: false,
+            validate: None,
+            normalizer: None,
+        })
+    }
+
+    /// Constructs a new map from a [`Deserializer`], interning the keys in `interner`.
+    ///
+    /// # Errors
+    ///
+    /// - the data does not deserializes as a map.
+    #[inline]
+    pub fn from_deserializer_with_interner<D>(
+        deserializer: D,
+        interner: &mut Interner<'bump>,
+        bump: &'bump Bump,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'bump>,
+    {
+        deserializer.deserialize_map(BumpRawMapVisitor {
+            bump,
+            hash_builder: DefaultHashBuilder::default(),
+            interner: Some(interner),
+            preserve_duplicates: false,
+            validate: None,
+            normalizer: None,
+        })
+    }
+
+    /// Constructs a new map from a [`Deserializer`], keeping all the occurrences of duplicated keys.
+    ///
+    /// See [`RawMap::push`] for the behavior of maps containing duplicated keys.
+    ///
+    /// # Errors
+    ///
+    /// - the data does not deserializes as a map.
+    #[inline]
+    pub fn from_deserializer_preserving_duplicates<D>(
+        deserializer: D,
+        bump: &'bump Bump,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'bump>,
+    {
+        deserializer.deserialize_map(BumpRawMapVisitor {
+            bump,
+            hash_builder: DefaultHashBuilder::default(),
+            interner: None,
+            preserve_duplicates: true,
+            validate: None,
+            normalizer: None,
+        })
+    }
+
+    /// Constructs a new map from a [`Deserializer`], calling `validate` on each entry.
+    ///
+    /// Parsing stops at the first entry for which `validate` returns an error.
+    ///
+    /// # Errors
+    ///
+    /// - the data does not deserializes as a map.
+    /// - an entry is rejected by `validate`.
+    #[inline]
+    pub(crate) fn from_deserializer_with_validator<D>(
+        deserializer: D,
+        validate: Validate<'_>,
+        bump: &'bump Bump,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'bump>,
+    {
+        deserializer.deserialize_map(BumpRawMapVisitor {
+            bump,
+            hash_builder: DefaultHashBuilder::default(),
+            interner: None,
+            preserve_duplicates: false,
+            validate: Some(validate),
+            normalizer: None,
         })
     }
 }
 
 impl<'bump, S: BuildHasher> RawMap<'bump, S> {
+    /// Constructs a new map from a [`Deserializer`] and a `HashBuilder`, normalizing the keys with `normalizer`.
+    ///
+    /// # Errors
+    ///
+    /// - the data does not deserializes as a map.
+    #[inline]
+    pub(crate) fn from_deserializer_normalized<D>(
+        deserializer: D,
+        normalizer: &dyn KeyNormalizer,
+        hash_builder: S,
+        bump: &'bump Bump,
+    ) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'bump>,
+    {
+        deserializer.deserialize_map(BumpRawMapVisitor {
+            bump,
+            hash_builder,
+            interner: None,
+            preserve_duplicates: false,
+            validate: None,
+            normalizer: Some(normalizer),
+        })
+    }
+
     /// Constructs a new map from a [`Deserializer`] and a `HashBuilder`.
     ///
     /// # Errors
@@ -78,6 +239,13 @@ impl<'bump, S: BuildHasher> RawMap<'bump, S> {
     where
         D: Deserializer<'bump>,
     {
-        deserializer.deserialize_map(BumpRawMapVisitor { bump, hash_builder })
+        deserializer.deserialize_map(BumpRawMapVisitor {
+            bump,
+            hash_builder,
+            interner: None,
+            preserve_duplicates: false,
+            validate: None,
+            normalizer: None,
+        })
     }
 }
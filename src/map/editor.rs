@@ -0,0 +1,388 @@
+use bumpalo::Bump;
+use serde::ser::{SerializeMap, SerializeSeq};
+use serde::Serialize;
+use serde_json::value::RawValue;
+
+use crate::value::{to_raw_value_in, Kind};
+use crate::{Error, RawMap, RawVec};
+
+/// A cursor staging modifications of nested objects and arrays of a [`RawMap`].
+///
+/// The cursor starts at the root object of the document, and can descend into nested objects
+/// with [`Self::enter`] and into the elements of arrays with [`Self::enter_index`].
+/// The modifications apply to the object or array the cursor points to.
+///
+/// [`Self::commit`] produces the updated map. Only the subtrees that were modified are serialized again
+/// into the bump, the other values are kept as-is.
+///
+/// ```
+/// use bumpalo::Bump;
+/// use bumparaw_collections::RawMap;
+/// use bumparaw_collections::map::DocumentEditor;
+/// use serde_json::value::RawValue;
+///
+/// let bump = Bump::new();
+/// let raw: &RawValue = serde_json::from_str(r#"{"id": 1, "doggo": {"name": "kefir", "toys": []}}"#).unwrap();
+/// let map = RawMap::from_raw_value(raw, &bump).unwrap();
+///
+/// let mut editor = DocumentEditor::new(&map);
+/// assert!(editor.enter("doggo").unwrap());
+/// editor.remove("name").unwrap();
+/// assert!(editor.enter("toys").unwrap());
+/// editor.append(serde_json::from_str(r#""ball""#).unwrap()).unwrap();
+///
+/// let map = editor.commit();
+/// assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"id":1,"doggo":{"toys":["ball"]}}"#);
+/// ```
+pub struct DocumentEditor<'bump> {
+    bump: &'bump Bump,
+    root: Node<'bump>,
+    cursor: Vec<Step<'bump>>,
+}
+
+enum Node<'bump> {
+    /// A value that the cursor never entered.
+    Raw(&'bump RawValue),
+    Object {
+        /// The original value, as long as the object is not modified.
+        original: Option<&'bump RawValue>,
+        entries: Vec<(&'bump str, Node<'bump>)>,
+    },
+    Array {
+        /// The original value, as long as the array is not modified.
+        original: Option<&'bump RawValue>,
+        values: Vec<Node<'bump>>,
+    },
+}
+
+#[derive(Clone, Copy)]
+enum Step<'bump> {
+    Key(&'bump str),
+    Index(usize),
+}
+
+impl<'bump> DocumentEditor<'bump> {
+    /// Constructs an editor pointing to the root of the map.
+    pub fn new<S>(map: &RawMap<'bump, S>) -> Self {
+        let entries = map.iter().map(|(key, value)| (key, Node::Raw(value)));
+        Self {
+            bump: map.bump(),
+            root: Node::Object {
+                original: None,
+                entries: entries.collect(),
+            },
+            cursor: Vec::new(),
+        }
+    }
+
+    /// Moves the cursor to the object or array associated with `key` in the current object.
+    ///
+    /// Returns `false` if the key is absent, in which case the cursor does not move.
+    ///
+    /// # Errors
+    ///
+    /// - if the cursor does not point to an object.
+    /// - if the value associated with `key` is neither an object nor an array.
+    pub fn enter(&mut self, key: &str) -> Result<bool, Error> {
+        let bump = self.bump;
+        let path = self.path();
+        let entries = self.current_object(false)?;
+        let Some((key, node)) = entries.iter_mut().find(|(k, _)| *k == key) else {
+            return Ok(false);
+        };
+        let key = *key;
+        node.expand(bump)
+            .map_err(|error| error.with_path(|| join_key(path, key)))?;
+        self.cursor.push(Step::Key(key));
+        Ok(true)
+    }
+
+    /// Moves the cursor to the object or array at `index` in the current array.
+    ///
+    /// Returns `false` if the index is out of bounds, in which case the cursor does not move.
+    ///
+    /// # Errors
+    ///
+    /// - if the cursor does not point to an array.
+    /// - if the value at `index` is neither an object nor an array.
+    pub fn enter_index(&mut self, index: usize) -> Result<bool, Error> {
+        let bump = self.bump;
+        let path = self.path();
+        let values = self.current_array(false)?;
+        let Some(node) = values.get_mut(index) else {
+            return Ok(false);
+        };
+        node.expand(bump)
+            .map_err(|error| error.with_path(|| join_index(path, index)))?;
+        self.cursor.push(Step::Index(index));
+        Ok(true)
+    }
+
+    /// Moves the cursor to the parent of the current object or array.
+    ///
+    /// Returns `false` if the cursor already points to the root of the document.
+    pub fn leave(&mut self) -> bool {
+        self.cursor.pop().is_some()
+    }
+
+    /// Moves the cursor to the root of the document.
+    pub fn leave_all(&mut self) {
+        self.cursor.clear();
+    }
+
+    /// Sets the value associated with `key` in the current object.
+    ///
+    /// If the key already exists, its position in the object is maintained.
+    ///
+    /// # Errors
+    ///
+    /// - if the cursor does not point to an object.
+    pub fn set(&mut self, key: &'bump str, value: &'bump RawValue) -> Result<(), Error> {
+        let entries = self.current_object(true)?;
+        match entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, node)) => *node = Node::Raw(value),
+            None => entries.push((key, Node::Raw(value))),
+        }
+        Ok(())
+    }
+
+    /// Removes the entry associated with `key` in the current object.
+    ///
+    /// Returns `false` if the key is absent.
+    ///
+    /// # Errors
+    ///
+    /// - if the cursor does not point to an object.
+    pub fn remove(&mut self, key: &str) -> Result<bool, Error> {
+        let entries = self.current_object(true)?;
+        match entries.iter().position(|(k, _)| *k == key) {
+            Some(index) => {
+                entries.remove(index);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Appends a value at the end of the current array.
+    ///
+    /// # Errors
+    ///
+    /// - if the cursor does not point to an array.
+    pub fn append(&mut self, value: &'bump RawValue) -> Result<(), Error> {
+        self.current_array(true)?.push(Node::Raw(value));
+        Ok(())
+    }
+
+    /// Sets the value at `index` in the current array.
+    ///
+    /// Returns `false` if the index is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// - if the cursor does not point to an array.
+    pub fn set_index(&mut self, index: usize, value: &'bump RawValue) -> Result<bool, Error> {
+        match self.current_array(true)?.get_mut(index) {
+            Some(node) => {
+                *node = Node::Raw(value);
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    /// Removes the value at `index` in the current array.
+    ///
+    /// Returns `false` if the index is out of bounds.
+    ///
+    /// # Errors
+    ///
+    /// - if the cursor does not point to an array.
+    pub fn remove_index(&mut self, index: usize) -> Result<bool, Error> {
+        let values = self.current_array(true)?;
+        if index < values.len() {
+            values.remove(index);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Produces the updated map, serializing the modified subtrees into the bump.
+    pub fn commit(self) -> RawMap<'bump> {
+        let bump = self.bump;
+        let Node::Object { entries, .. } = self.root else {
+            unreachable!("the root of a document is always an object")
+        };
+        let mut map = RawMap::with_capacity_in(entries.len(), bump);
+        for (key, node) in entries {
+            map.insert(key, node.into_raw_value(bump));
+        }
+        map
+    }
+
+    /// The path of the current object or array, `None` at the root.
+    fn path(&self) -> Option<String> {
+        let mut steps = self.cursor.iter();
+        let mut path = match steps.next()? {
+            Step::Key(key) => key.to_string(),
+            Step::Index(index) => format!("[{index}]"),
+        };
+        for step in steps {
+            path = match step {
+                Step::Key(key) => join_key(Some(path), key),
+                Step::Index(index) => join_index(Some(path), *index),
+            };
+        }
+        Some(path)
+    }
+
+    /// Walks from the root to the node the cursor points to,
+    /// discarding the original values along the way if the node is about to be modified.
+    fn current(&mut self, modify: bool) -> &mut Node<'bump> {
+        let mut node = &mut self.root;
+        for step in &self.cursor {
+            if modify {
+                node.discard_original();
+            }
+            node = match (node, *step) {
+                (Node::Object { entries, .. }, Step::Key(key)) => {
+                    &mut entries.iter_mut().find(|(k, _)| *k == key).unwrap().1
+                }
+                (Node::Array { values, .. }, Step::Index(index)) => &mut values[index],
+                _ => unreachable!("the cursor only points to expanded nodes"),
+            };
+        }
+        if modify {
+            node.discard_original();
+        }
+        node
+    }
+
+    fn current_object(
+        &mut self,
+        modify: bool,
+    ) -> Result<&mut Vec<(&'bump str, Node<'bump>)>, Error> {
+        let path = self.path();
+        match self.current(modify) {
+            Node::Object { entries, .. } => Ok(entries),
+            _ => Err(Error::WrongKind {
+                path,
+                expected: Kind::Object,
+                found: Kind::Array,
+            }),
+        }
+    }
+
+    fn current_array(&mut self, modify: bool) -> Result<&mut Vec<Node<'bump>>, Error> {
+        let path = self.path();
+        match self.current(modify) {
+            Node::Array { values, .. } => Ok(values),
+            _ => Err(Error::WrongKind {
+                path,
+                expected: Kind::Array,
+                found: Kind::Object,
+            }),
+        }
+    }
+}
+
+impl<'bump> Node<'bump> {
+    /// Parses the top level of a raw object or array.
+    fn expand(&mut self, bump: &'bump Bump) -> Result<(), Error> {
+        let Node::Raw(raw) = *self else {
+            return Ok(());
+        };
+        *self = match Kind::of(raw) {
+            Kind::Object => {
+                let map = RawMap::from_raw_value(raw, bump)?;
+                Node::Object {
+                    original: Some(raw),
+                    entries: map.iter().map(|(k, v)| (k, Node::Raw(v))).collect(),
+                }
+            }
+            Kind::Array => {
+                let array = RawVec::from_raw_value(raw, bump)?;
+                Node::Array {
+                    original: Some(raw),
+                    values: array.iter().map(Node::Raw).collect(),
+                }
+            }
+            found => {
+                return Err(Error::WrongKind {
+                    path: None,
+                    expected: Kind::Object,
+                    found,
+                })
+            }
+        };
+        Ok(())
+    }
+
+    fn discard_original(&mut self) {
+        match self {
+            Node::Object { original, .. } | Node::Array { original, .. } => *original = None,
+            Node::Raw(_) => (),
+        }
+    }
+
+    fn into_raw_value(self, bump: &'bump Bump) -> &'bump RawValue {
+        match self {
+            Node::Raw(raw)
+            | Node::Object {
+                original: Some(raw),
+                ..
+            }
+            | Node::Array {
+                original: Some(raw),
+                ..
+            } => raw,
+            // string keys and raw values are always serializable
+            node => to_raw_value_in(&node, bump).unwrap(),
+        }
+    }
+}
+
+impl Serialize for Node<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        match self {
+            Node::Raw(raw)
+            | Node::Object {
+                original: Some(raw),
+                ..
+            }
+            | Node::Array {
+                original: Some(raw),
+                ..
+            } => raw.serialize(serializer),
+            Node::Object { entries, .. } => {
+                let mut map = serializer.serialize_map(Some(entries.len()))?;
+                for (key, node) in entries {
+                    map.serialize_entry(key, node)?;
+                }
+                map.end()
+            }
+            Node::Array { values, .. } => {
+                let mut seq = serializer.serialize_seq(Some(values.len()))?;
+                for node in values {
+                    seq.serialize_element(node)?;
+                }
+                seq.end()
+            }
+        }
+    }
+}
+
+fn join_key(path: Option<String>, key: &str) -> String {
+    match path {
+        Some(path) => format!("{path}.{key}"),
+        None => key.to_string(),
+    }
+}
+
+fn join_index(path: Option<String>, index: usize) -> String {
+    format!("{}[{index}]", path.unwrap_or_default())
+}
@@ -0,0 +1,290 @@
+use std::hash::BuildHasher;
+
+use bumpalo::collections::Vec as BVec;
+use bumpalo::Bump;
+use hashbrown::hash_map::{RawEntryMut as HashRawEntryMut, RawVacantEntryMut};
+use serde_json::value::RawValue;
+
+use crate::RawMap;
+
+/// A view into a single entry of a [`RawMap`], looked up by a borrowed key.
+///
+/// This is constructed by [`RawMap::entry_ref`].
+pub enum EntryRef<'a, 'k, 'bump, S> {
+    /// The key is present in the map.
+    Occupied(OccupiedEntry<'a, 'bump>),
+    /// The key is absent from the map.
+    Vacant(VacantEntryRef<'a, 'k, 'bump, S>),
+}
+
+/// A view into an occupied entry of a [`RawMap`].
+pub struct OccupiedEntry<'a, 'bump> {
+    data: &'a mut BVec<'bump, (&'bump str, &'bump RawValue)>,
+    index: usize,
+}
+
+/// A view into a vacant entry of a [`RawMap`], holding a borrowed key.
+///
+/// The key is only copied into the bump when a value is inserted.
+pub struct VacantEntryRef<'a, 'k, 'bump, S> {
+    data: &'a mut BVec<'bump, (&'bump str, &'bump RawValue)>,
+    entry: RawVacantEntryMut<'a, &'bump str, usize, S, &'bump Bump>,
+    hash: u64,
+    key: &'k str,
+}
+
+impl<'bump, S: BuildHasher> RawMap<'bump, S> {
+    /// Gets the entry of a borrowed key for in-place manipulation.
+    ///
+    /// Unlike [`Self::insert`], the key does not need to be allocated in the bump beforehand:
+    /// it is only copied into the bump if the entry is vacant and a value is inserted.
+    #[inline]
+    pub fn entry_ref<'a, 'k>(&'a mut self, key: &'k str) -> EntryRef<'a, 'k, 'bump, S> {
+        let hash = self.cache.hasher().hash_one(key);
+        match self
+            .cache
+            .raw_entry_mut()
+            .from_key_hashed_nocheck(hash, key)
+        {
+            HashRawEntryMut::Occupied(entry) => EntryRef::Occupied(OccupiedEntry {
+                data: &mut self.data,
+                index: *entry.get(),
+            }),
+            HashRawEntryMut::Vacant(entry) => EntryRef::Vacant(VacantEntryRef {
+                data: &mut self.data,
+                entry,
+                hash,
+                key,
+            }),
+        }
+    }
+}
+
+impl<'bump, S> RawMap<'bump, S> {
+    /// Returns a reference to the hasher of the map, to compute the hashes given to the raw entry API.
+    #[inline]
+    pub fn hasher(&self) -> &S {
+        self.cache.hasher()
+    }
+
+    /// Creates a raw immutable entry builder for the map.
+    ///
+    /// Raw entries allow looking up a key by a precomputed hash, that must be computed with [`Self::hasher`],
+    /// and a matching function. Unless hash memoization is needed, [`Self::get`] should be preferred.
+    #[inline]
+    pub fn raw_entry(&self) -> RawEntryBuilder<'_, 'bump, S> {
+        RawEntryBuilder { map: self }
+    }
+
+    /// Creates a raw entry builder for the map.
+    ///
+    /// Raw entries allow looking up a key by a precomputed hash, that must be computed with [`Self::hasher`],
+    /// and a matching function, then inserting with that same hash without hashing the key again.
+    ///
+    /// The hash used to look up or insert an entry must be consistent with the hash of its key,
+    /// otherwise the map will produce seemingly random results.
+    /// Unless hash memoization is needed, [`Self::entry_ref`] should be preferred.
+    #[inline]
+    pub fn raw_entry_mut(&mut self) -> RawEntryBuilderMut<'_, 'bump, S> {
+        RawEntryBuilderMut { map: self }
+    }
+}
+
+/// A builder for looking up an entry of a [`RawMap`] by a precomputed hash.
+///
+/// This is constructed by [`RawMap::raw_entry`].
+pub struct RawEntryBuilder<'a, 'bump, S> {
+    map: &'a RawMap<'bump, S>,
+}
+
+impl<'bump, S> RawEntryBuilder<'_, 'bump, S> {
+    /// Looks up the entry of the key, given its hash.
+    #[allow(clippy::wrong_self_convention)] // blame hashbrown
+    #[inline]
+    pub fn from_key_hashed_nocheck(
+        self,
+        hash: u64,
+        key: &str,
+    ) -> Option<(&'bump str, &'bump RawValue)> {
+        self.from_hash(hash, |k| k == key)
+    }
+
+    /// Looks up the entry matching `is_match`, given its hash.
+    #[allow(clippy::wrong_self_convention)] // blame hashbrown
+    #[inline]
+    pub fn from_hash<F>(self, hash: u64, mut is_match: F) -> Option<(&'bump str, &'bump RawValue)>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let (_, index) = self
+            .map
+            .cache
+            .raw_entry()
+            .from_hash(hash, |k| is_match(k))?;
+        Some(self.map.data[*index])
+    }
+}
+
+/// A builder for looking up an entry of a [`RawMap`] by a precomputed hash, for in-place manipulation.
+///
+/// This is constructed by [`RawMap::raw_entry_mut`].
+pub struct RawEntryBuilderMut<'a, 'bump, S> {
+    map: &'a mut RawMap<'bump, S>,
+}
+
+/// A view into a single entry of a [`RawMap`], looked up by a precomputed hash.
+///
+/// This is constructed by [`RawEntryBuilderMut`].
+pub enum RawEntryMut<'a, 'bump, S> {
+    /// The key is present in the map.
+    Occupied(OccupiedEntry<'a, 'bump>),
+    /// The key is absent from the map.
+    Vacant(RawVacantEntry<'a, 'bump, S>),
+}
+
+/// A view into a vacant entry of a [`RawMap`], looked up by a precomputed hash.
+pub struct RawVacantEntry<'a, 'bump, S> {
+    data: &'a mut BVec<'bump, (&'bump str, &'bump RawValue)>,
+    entry: RawVacantEntryMut<'a, &'bump str, usize, S, &'bump Bump>,
+}
+
+impl<'a, 'bump, S> RawEntryBuilderMut<'a, 'bump, S> {
+    /// Accesses the entry of the key, given its hash.
+    #[allow(clippy::wrong_self_convention)] // blame hashbrown
+    #[inline]
+    pub fn from_key_hashed_nocheck(self, hash: u64, key: &str) -> RawEntryMut<'a, 'bump, S> {
+        self.from_hash(hash, |k| k == key)
+    }
+
+    /// Accesses the entry matching `is_match`, given its hash.
+    #[allow(clippy::wrong_self_convention)] // blame hashbrown
+    #[inline]
+    pub fn from_hash<F>(self, hash: u64, mut is_match: F) -> RawEntryMut<'a, 'bump, S>
+    where
+        F: FnMut(&str) -> bool,
+    {
+        let map = self.map;
+        match map.cache.raw_entry_mut().from_hash(hash, |k| is_match(k)) {
+            HashRawEntryMut::Occupied(entry) => RawEntryMut::Occupied(OccupiedEntry {
+                data: &mut map.data,
+                index: *entry.get(),
+            }),
+            HashRawEntryMut::Vacant(entry) => RawEntryMut::Vacant(RawVacantEntry {
+                data: &mut map.data,
+                entry,
+            }),
+        }
+    }
+}
+
+impl<'a, 'bump, S: BuildHasher> RawVacantEntry<'a, 'bump, S> {
+    /// Inserts the entry at the end of the map, hashing the key.
+    #[inline]
+    pub fn insert(self, key: &'bump str, value: &'bump RawValue) -> &'a mut &'bump RawValue {
+        let index = self.data.len();
+        self.entry.insert(key, index);
+        self.data.push((key, value));
+        &mut self.data[index].1
+    }
+
+    /// Inserts the entry at the end of the map with the given hash, without hashing the key again.
+    ///
+    /// The hash must be the hash of `key`.
+    #[inline]
+    pub fn insert_hashed_nocheck(
+        self,
+        hash: u64,
+        key: &'bump str,
+        value: &'bump RawValue,
+    ) -> &'a mut &'bump RawValue {
+        let index = self.data.len();
+        self.entry.insert_hashed_nocheck(hash, key, index);
+        self.data.push((key, value));
+        &mut self.data[index].1
+    }
+}
+
+impl<'a, 'bump, S: BuildHasher> EntryRef<'a, '_, 'bump, S> {
+    /// Returns the value of the entry, inserting `default` if the entry is vacant.
+    #[inline]
+    pub fn or_insert(self, default: &'bump RawValue) -> &'a mut &'bump RawValue {
+        self.or_insert_with(|| default)
+    }
+
+    /// Returns the value of the entry, inserting the result of `default` if the entry is vacant.
+    #[inline]
+    pub fn or_insert_with<F>(self, default: F) -> &'a mut &'bump RawValue
+    where
+        F: FnOnce() -> &'bump RawValue,
+    {
+        match self {
+            EntryRef::Occupied(entry) => entry.into_mut(),
+            EntryRef::Vacant(entry) => entry.insert(default()),
+        }
+    }
+
+    /// The key of the entry.
+    #[inline]
+    pub fn key(&self) -> &str {
+        match self {
+            EntryRef::Occupied(entry) => entry.key(),
+            EntryRef::Vacant(entry) => entry.key(),
+        }
+    }
+}
+
+impl<'a, 'bump> OccupiedEntry<'a, 'bump> {
+    /// The key of the entry, as allocated in the bump.
+    #[inline]
+    pub fn key(&self) -> &'bump str {
+        self.data[self.index].0
+    }
+
+    /// The index of the entry in the data slice.
+    #[inline]
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    /// The value of the entry.
+    #[inline]
+    pub fn get(&self) -> &'bump RawValue {
+        self.data[self.index].1
+    }
+
+    /// A mutable reference to the value of the entry.
+    #[inline]
+    pub fn get_mut(&mut self) -> &mut &'bump RawValue {
+        &mut self.data[self.index].1
+    }
+
+    /// Converts the entry into a mutable reference to its value.
+    #[inline]
+    pub fn into_mut(self) -> &'a mut &'bump RawValue {
+        &mut self.data[self.index].1
+    }
+
+    /// Sets the value of the entry, returning the previous value.
+    #[inline]
+    pub fn insert(&mut self, value: &'bump RawValue) -> &'bump RawValue {
+        std::mem::replace(self.get_mut(), value)
+    }
+}
+
+impl<'a, 'k, 'bump, S: BuildHasher> VacantEntryRef<'a, 'k, 'bump, S> {
+    /// The borrowed key of the entry.
+    #[inline]
+    pub fn key(&self) -> &'k str {
+        self.key
+    }
+
+    /// Copies the key into the bump and inserts the entry at the end of the map.
+    #[inline]
+    pub fn insert(self, value: &'bump RawValue) -> &'a mut &'bump RawValue {
+        let key = self.data.bump().alloc_str(self.key);
+        let index = self.data.len();
+        self.entry.insert_hashed_nocheck(self.hash, key, index);
+        self.data.push((key, value));
+        &mut self.data[index].1
+    }
+}
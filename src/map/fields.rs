@@ -0,0 +1,105 @@
+use bumpalo::Bump;
+
+use crate::{Error, RawMap};
+
+impl<'bump> RawMap<'bump> {
+    /// Constructs a map from fields identified by ids, as stored in field-id keyed buffers such as obkv.
+    ///
+    /// `resolve` gives the name of the key associated with a field id.
+    /// The fields whose id it does not know are skipped.
+    /// The values are copied into the bump, so that the fields can borrow a short-lived buffer.
+    ///
+    /// # Errors
+    ///
+    /// - if a value is not valid JSON. The path of the error is the name of the field.
+    pub fn from_fields_in<'f, K, I, R>(
+        fields: I,
+        mut resolve: R,
+        bump: &'bump Bump,
+    ) -> Result<Self, Error>
+    where
+        I: IntoIterator<Item = (K, &'f [u8])>,
+        R: FnMut(K) -> Option<&'bump str>,
+    {
+        let fields = fields.into_iter();
+        let mut map = RawMap::with_capacity_in(fields.size_hint().0, bump);
+        for (id, bytes) in fields {
+            let Some(key) = resolve(id) else {
+                continue;
+            };
+            let invalid = |source| Error::Parse {
+                path: Some(key.to_owned()),
+                offset: None,
+                source,
+            };
+            let value = std::str::from_utf8(bytes)
+                .map_err(|error| invalid(serde::de::Error::custom(error)))?;
+            let value = serde_json::from_str(bump.alloc_str(value))
+                .map_err(|error| Error::from_input(value, error).with_path(|| key.to_owned()))?;
+            map.insert(key, value);
+        }
+        Ok(map)
+    }
+
+    /// Constructs a map from the fields of an obkv buffer, whose values are JSON values.
+    ///
+    /// See [`Self::from_fields_in`]: `resolve` gives the name of the key associated with a field id,
+    /// and the values are copied into the bump, so that the buffer can be short-lived.
+    ///
+    /// # Errors
+    ///
+    /// - if a value is not valid JSON. The path of the error is the name of the field.
+    #[cfg(feature = "obkv")]
+    pub fn from_obkv_in<K, R>(
+        reader: &obkv::KvReader<K>,
+        resolve: R,
+        bump: &'bump Bump,
+    ) -> Result<Self, Error>
+    where
+        K: obkv::Key,
+        R: FnMut(K) -> Option<&'bump str>,
+    {
+        Self::from_fields_in(reader.iter(), resolve, bump)
+    }
+}
+
+impl<'bump, S> RawMap<'bump, S> {
+    /// Returns the entries of the map as fields identified by ids, sorted by id.
+    ///
+    /// `resolve` gives the field id associated with a key. The keys it does not know are skipped.
+    /// The fields are in the increasing order that writers of field-id keyed buffers such as obkv expect.
+    pub fn to_fields<K, R>(&self, mut resolve: R) -> Vec<(K, &'bump [u8])>
+    where
+        K: Ord,
+        R: FnMut(&str) -> Option<K>,
+    {
+        let mut fields: Vec<_> = self
+            .iter()
+            .filter_map(|(key, value)| Some((resolve(key)?, value.get().as_bytes())))
+            .collect();
+        fields.sort_by(|(left, _), (right, _)| left.cmp(right));
+        fields
+    }
+    /// Writes the entries of the map as the fields of an obkv buffer.
+    ///
+    /// See [`Self::to_fields`]: `resolve` gives the field id associated with a key,
+    /// and the keys it does not know are skipped. The fields are inserted in the increasing order of their ids.
+    ///
+    /// # Errors
+    ///
+    /// - if writing fails.
+    /// - if two keys are resolved to the same field id.
+    #[cfg(feature = "obkv")]
+    pub fn write_obkv<W, K, R>(
+        &self,
+        writer: &mut obkv::KvWriter<W, K>,
+        resolve: R,
+    ) -> std::io::Result<()>
+    where
+        W: std::io::Write,
+        K: obkv::Key + Ord,
+        R: FnMut(&str) -> Option<K>,
+    {
+        writer.extend(self.to_fields(resolve))
+    }
+}
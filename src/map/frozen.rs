@@ -28,6 +28,21 @@ where
 {
 }
 
+/// SAFETY:
+///
+/// - K, V, S are [`Sync`]
+/// - The methods taking `&self` only read the inner hashmap, and never allocate nor deallocate.
+/// - The FrozenMap does not leak a shared reference to the allocator **or its inner hashmap**.
+///
+/// So, it is safe to share the FrozenMap between threads.
+unsafe impl<K, V, S> Sync for FrozenMap<'_, '_, K, V, S>
+where
+    K: Sync,
+    V: Sync,
+    S: Sync,
+{
+}
+
 impl<'a, 'bump, K, V, S> FrozenMap<'a, 'bump, K, V, S> {
     /// Makes the passed map [`Send`] by preventing any future modifications.
     #[inline]
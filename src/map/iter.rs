@@ -1,6 +1,11 @@
+use bumpalo::Bump;
+use serde::Deserializer as _;
 use serde_json::value::RawValue;
 
-use crate::RawMap;
+use crate::map::de::BumpEntriesVisitor;
+use crate::map::FrozenRawMap;
+use crate::value::Kind;
+use crate::{RawMap, RawVec};
 
 /// An iterator over the keys of a [`RawMap`].
 ///
@@ -97,4 +102,171 @@ impl<'bump, S> RawMap<'bump, S> {
     pub fn values(&self) -> Values<'bump, '_> {
         Values(self.data.iter())
     }
+
+    /// Iterates depth-first over the scalar leaves of the map, along with their dotted path.
+    ///
+    /// See [`Leaves`] for more information.
+    #[inline]
+    pub fn leaves(&self) -> Leaves<'bump, '_> {
+        Leaves {
+            bump: self.bump(),
+            stack: vec![Frame::Object {
+                path: None,
+                entries: self.data.iter(),
+            }],
+        }
+    }
+}
+
+impl<'bump, S> RawMap<'bump, S> {
+    /// Iterates over the (key, value) pairs at the indices of `order`, in the order of `order`.
+    ///
+    /// `order` is typically a permutation of the indices of the entries, but may skip or repeat indices.
+    /// The indices are checked once, before iterating.
+    ///
+    /// # Panics
+    ///
+    /// - if an index of `order` is out of bounds.
+    #[inline]
+    pub fn iter_in_order<'o>(&self, order: &'o [usize]) -> InOrder<'bump, '_, 'o> {
+        InOrder::new(&self.data, order)
+    }
+}
+
+impl<'a, 'bump, S> FrozenRawMap<'a, 'bump, S> {
+    /// Iterates over the (key, value) pairs at the indices of `order`, in the order of `order`.
+    ///
+    /// See [`RawMap::iter_in_order`].
+    ///
+    /// # Panics
+    ///
+    /// - if an index of `order` is out of bounds.
+    #[inline]
+    pub fn iter_in_order<'o>(&self, order: &'o [usize]) -> InOrder<'bump, 'a, 'o> {
+        InOrder::new(self.as_slice(), order)
+    }
+}
+
+/// An iterator over the (key, value) pairs of a map in a caller-provided order.
+///
+/// See [`RawMap::iter_in_order`].
+pub struct InOrder<'bump, 'a, 'o> {
+    data: &'a [(&'bump str, &'bump RawValue)],
+    order: std::slice::Iter<'o, usize>,
+}
+
+impl<'bump, 'a, 'o> InOrder<'bump, 'a, 'o> {
+    fn new(data: &'a [(&'bump str, &'bump RawValue)], order: &'o [usize]) -> Self {
+        if let Some(&max) = order.iter().max() {
+            assert!(
+                max < data.len(),
+                "index {max} out of bounds for a map of length {}",
+                data.len()
+            );
+        }
+        Self {
+            data,
+            order: order.iter(),
+        }
+    }
+}
+
+impl<'bump> Iterator for InOrder<'bump, '_, '_> {
+    type Item = (&'bump str, &'bump RawValue);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.order.next().map(|&index| self.data[index])
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.order.size_hint()
+    }
+}
+
+impl ExactSizeIterator for InOrder<'_, '_, '_> {}
+
+/// A depth-first iterator over the scalar leaves of a [`RawMap`], yielding `(path, value)` pairs.
+///
+/// The path of a leaf is made of the keys leading to it, separated by dots, and is allocated in the bump
+/// when the leaf is nested. The elements of an array share the path of the array.
+/// Empty objects and arrays are not leaves and are skipped.
+///
+/// The nested objects and arrays are parsed in the bump as they are reached, into plain slices of entries
+/// and values that are not indexed, so all the occurrences of the keys duplicated in a nested object are visited.
+pub struct Leaves<'bump, 'a> {
+    bump: &'bump Bump,
+    stack: Vec<Frame<'bump, 'a>>,
+}
+
+enum Frame<'bump, 'a> {
+    Object {
+        path: Option<&'bump str>,
+        entries: std::slice::Iter<'a, (&'bump str, &'bump RawValue)>,
+    },
+    Array {
+        path: &'bump str,
+        values: std::slice::Iter<'bump, &'bump RawValue>,
+    },
+}
+
+impl<'bump> Iterator for Leaves<'bump, '_> {
+    type Item = (&'bump str, &'bump RawValue);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (path, value) = match self.stack.last_mut()? {
+                Frame::Object { path, entries } => match entries.next() {
+                    Some(&(key, value)) => match path {
+                        Some(path) => {
+                            let path = bumpalo::format!(in self.bump, "{}.{}", path, key);
+                            (path.into_bump_str(), value)
+                        }
+                        None => (key, value),
+                    },
+                    None => {
+                        self.stack.pop();
+                        continue;
+                    }
+                },
+                Frame::Array { path, values } => match values.next() {
+                    Some(&value) => (*path, value),
+                    None => {
+                        self.stack.pop();
+                        continue;
+                    }
+                },
+            };
+
+            // a `RawValue` of the object or array kind is always a valid object or array
+            match Kind::of(value) {
+                Kind::Object => {
+                    let entries = value
+                        .deserialize_map(BumpEntriesVisitor(self.bump))
+                        .unwrap();
+                    self.stack.push(Frame::Object {
+                        path: Some(path),
+                        entries: entries.into_bump_slice().iter(),
+                    });
+                }
+                Kind::Array => {
+                    let array = RawVec::from_raw_value(value, self.bump).unwrap();
+                    self.stack.push(Frame::Array {
+                        path,
+                        values: array.into_bump_slice().iter(),
+                    });
+                }
+                _ => return Some((path, value)),
+            }
+        }
+    }
+}
+
+impl<'bump, S> FrozenRawMap<'_, 'bump, S> {
+    /// Iterates over the keys of the map in first-insertion order.
+    #[inline]
+    pub fn keys(&self) -> Keys<'bump, '_> {
+        Keys(self.as_slice().iter())
+    }
 }
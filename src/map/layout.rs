@@ -0,0 +1,300 @@
+use std::fmt;
+use std::io::{self, Write};
+
+use bumpalo::Bump;
+use serde_json::value::RawValue;
+
+use crate::map::FrozenRawMap;
+use crate::{Error, RawMap};
+
+const MAGIC: &[u8; 4] = b"brm1";
+const HEADER_LEN: usize = 8;
+const ENTRY_LEN: usize = 16;
+const INDEX_LEN: usize = 4;
+
+impl<S> FrozenRawMap<'_, '_, S> {
+    /// Writes the map to `writer` using the layout that [`RawMapBytes`] reads.
+    ///
+    /// # Errors
+    ///
+    /// - if writing fails.
+    /// - if the map or its data section is larger than [`u32::MAX`].
+    pub fn write_to<W: Write>(&self, writer: W) -> io::Result<()> {
+        write_entries(self.as_slice(), writer)
+    }
+}
+
+/// A read-only view over a map written with [`FrozenRawMap::write_to`].
+///
+/// The view borrows the bytes (e.g., a memory-mapped file) and performs lookups directly on them,
+/// without parsing nor copying the entries.
+/// Values are returned as JSON text, that can be turned into a [`serde_json::value::RawValue`]
+/// with [`serde_json::from_str`] when needed.
+///
+/// # Layout
+///
+/// All integers are little-endian `u32`s. A map of `N` entries is laid out as follows:
+///
+/// | Size       | Content                                                                    |
+/// |------------|----------------------------------------------------------------------------|
+/// | 4          | the magic bytes `brm1`                                                     |
+/// | 4          | `N`, the number of entries                                                 |
+/// | `16 * N`   | the entry table, in insertion order: key offset, key length, value offset, value length |
+/// | `4 * N`    | the lookup table: the entry indices, sorted by key bytes                   |
+/// | remaining  | the data section: concatenated key and JSON value bytes                    |
+///
+/// Offsets are relative to the start of the data section.
+#[derive(Clone, Copy)]
+pub struct RawMapBytes<'a> {
+    entries: &'a [u8],
+    sorted: &'a [u8],
+    data: &'a str,
+}
+
+impl<'a> RawMapBytes<'a> {
+    /// Constructs a view over the bytes of a map.
+    ///
+    /// The structure of the layout is checked, and the data section is validated as UTF-8.
+    /// See [`Self::from_bytes_unchecked`] to skip these checks on trusted bytes.
+    ///
+    /// # Errors
+    ///
+    /// - if the bytes do not follow the layout.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, InvalidLayoutError> {
+        let (header, rest) = bytes
+            .split_at_checked(HEADER_LEN)
+            .ok_or(InvalidLayoutError("truncated header"))?;
+        if &header[..4] != MAGIC {
+            return Err(InvalidLayoutError("invalid magic bytes"));
+        }
+        let len = read_u32(header, 4) as usize;
+        let (entries, rest) = len
+            .checked_mul(ENTRY_LEN)
+            .and_then(|size| rest.split_at_checked(size))
+            .ok_or(InvalidLayoutError("truncated entry table"))?;
+        let (sorted, data) = rest
+            .split_at_checked(len * INDEX_LEN)
+            .ok_or(InvalidLayoutError("truncated lookup table"))?;
+        let data =
+            std::str::from_utf8(data).map_err(|_| InvalidLayoutError("invalid UTF-8 data"))?;
+
+        let view = Self {
+            entries,
+            sorted,
+            data,
+        };
+        for index in 0..len {
+            view.try_entry(index)
+                .ok_or(InvalidLayoutError("entry out of bounds"))?;
+            if read_u32(sorted, index * INDEX_LEN) as usize >= len {
+                return Err(InvalidLayoutError("lookup index out of bounds"));
+            }
+        }
+        Ok(view)
+    }
+
+    /// Constructs a view over the bytes of a map, without checking them.
+    ///
+    /// Unlike [`Self::from_bytes`], whose checks are linear in the size of the map, this takes constant time.
+    /// It is meant for the bytes that are known to be valid, e.g., bytes that were checked when stored.
+    ///
+    /// # Safety
+    ///
+    /// The bytes must follow the layout, that is, [`Self::from_bytes`] must succeed on them.
+    /// In particular, the data section must be valid UTF-8.
+    ///
+    /// # Panics
+    ///
+    /// - if the bytes are too short for the number of entries in their header.
+    pub unsafe fn from_bytes_unchecked(bytes: &'a [u8]) -> Self {
+        let len = read_u32(bytes, 4) as usize;
+        let (entries, rest) = bytes[HEADER_LEN..].split_at(len * ENTRY_LEN);
+        let (sorted, data) = rest.split_at(len * INDEX_LEN);
+        Self {
+            entries,
+            sorted,
+            // SAFETY: the caller guarantees that the data section is valid UTF-8
+            data: unsafe { std::str::from_utf8_unchecked(data) },
+        }
+    }
+
+    /// The number of entries in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.entries.len() / ENTRY_LEN
+    }
+
+    /// `true` if there are no entries in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Returns the (key, JSON value) pair at `index` in insertion order, if in bounds.
+    #[inline]
+    pub fn get_entry(&self, index: usize) -> Option<(&'a str, &'a str)> {
+        if index < self.len() {
+            self.try_entry(index)
+        } else {
+            None
+        }
+    }
+
+    /// Retrieves the index of a key in insertion order, if present.
+    ///
+    /// The lookup is a binary search over the lookup table.
+    pub fn get_index(&self, key: &str) -> Option<usize> {
+        let mut low = 0;
+        let mut high = self.len();
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let index = read_u32(self.sorted, middle * INDEX_LEN) as usize;
+            let (candidate, _) = self.try_entry(index)?;
+            match candidate.as_bytes().cmp(key.as_bytes()) {
+                std::cmp::Ordering::Less => low = middle + 1,
+                std::cmp::Ordering::Greater => high = middle,
+                std::cmp::Ordering::Equal => return Some(index),
+            }
+        }
+        None
+    }
+
+    /// Retrieves the JSON value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        let index = self.get_index(key)?;
+        self.try_entry(index).map(|(_, value)| value)
+    }
+
+    /// Iterates over the (key, JSON value) pairs in insertion order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        let view = *self;
+        (0..view.len()).filter_map(move |index| view.try_entry(index))
+    }
+
+    /// Copies the entries into `bump` and constructs a [`RawMap`] from them.
+    ///
+    /// Together with [`FrozenRawMap::write_to`], this is the decoding half of storing maps
+    /// in a key-value store whose values only live as long as a read transaction.
+    ///
+    /// # Errors
+    ///
+    /// - if a value is not valid JSON. The path of the error is the key.
+    pub fn to_raw_map_in<'bump>(&self, bump: &'bump Bump) -> Result<RawMap<'bump>, Error> {
+        let mut map = RawMap::with_capacity_in(self.len(), bump);
+        for (key, value) in self.iter() {
+            let value: &'bump RawValue = serde_json::from_str(bump.alloc_str(value))
+                .map_err(|error| Error::from_input(value, error).with_path(|| key.to_owned()))?;
+            map.insert(bump.alloc_str(key), value);
+        }
+        Ok(map)
+    }
+
+    fn try_entry(&self, index: usize) -> Option<(&'a str, &'a str)> {
+        let base = index * ENTRY_LEN;
+        let key = self.slice(
+            read_u32(self.entries, base),
+            read_u32(self.entries, base + 4),
+        )?;
+        let value = self.slice(
+            read_u32(self.entries, base + 8),
+            read_u32(self.entries, base + 12),
+        )?;
+        Some((key, value))
+    }
+
+    fn slice(&self, offset: u32, len: u32) -> Option<&'a str> {
+        let start = offset as usize;
+        self.data.get(start..start.checked_add(len as usize)?)
+    }
+}
+
+impl fmt::Debug for RawMapBytes<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A [`heed`] codec storing maps with the layout of [`RawMapBytes`].
+///
+/// The maps are encoded from their entries, e.g., [`FrozenRawMap::as_slice`], and are decoded as views
+/// borrowing the bytes of the read transaction. Use [`RawMapBytes::to_raw_map_in`] to copy a decoded map
+/// into a bump provided by the caller, so that it outlives the transaction.
+#[cfg(feature = "heed")]
+pub enum RawMapCodec {}
+
+#[cfg(feature = "heed")]
+impl<'a> heed::BytesEncode<'a> for RawMapCodec {
+    type EItem = [(&'a str, &'a RawValue)];
+
+    fn bytes_encode(
+        entries: &'a Self::EItem,
+    ) -> Result<std::borrow::Cow<'a, [u8]>, heed::BoxedError> {
+        let mut bytes = Vec::new();
+        write_entries(entries, &mut bytes)?;
+        Ok(bytes.into())
+    }
+}
+
+#[cfg(feature = "heed")]
+impl<'a> heed::BytesDecode<'a> for RawMapCodec {
+    type DItem = RawMapBytes<'a>;
+
+    fn bytes_decode(bytes: &'a [u8]) -> Result<Self::DItem, heed::BoxedError> {
+        Ok(RawMapBytes::from_bytes(bytes)?)
+    }
+}
+
+/// The error returned when bytes do not follow the layout expected by [`RawMapBytes`].
+#[derive(Debug, Clone, Copy)]
+pub struct InvalidLayoutError(&'static str);
+
+impl fmt::Display for InvalidLayoutError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid map layout: {}", self.0)
+    }
+}
+
+impl std::error::Error for InvalidLayoutError {}
+
+/// Writes the entries using the layout that [`RawMapBytes`] reads.
+fn write_entries<W: Write>(entries: &[(&str, &RawValue)], mut writer: W) -> io::Result<()> {
+    let too_large = || io::Error::new(io::ErrorKind::InvalidInput, "map too large");
+    let len = u32::try_from(entries.len()).map_err(|_| too_large())?;
+
+    writer.write_all(MAGIC)?;
+    writer.write_all(&len.to_le_bytes())?;
+
+    let mut offset = 0u32;
+    let mut push = |len: usize| -> io::Result<[u8; 8]> {
+        let len = u32::try_from(len).map_err(|_| too_large())?;
+        let start = offset;
+        offset = offset.checked_add(len).ok_or_else(too_large)?;
+        let mut bytes = [0; 8];
+        bytes[..4].copy_from_slice(&start.to_le_bytes());
+        bytes[4..].copy_from_slice(&len.to_le_bytes());
+        Ok(bytes)
+    };
+    for (key, value) in entries {
+        writer.write_all(&push(key.len())?)?;
+        writer.write_all(&push(value.get().len())?)?;
+    }
+
+    let mut sorted: Vec<u32> = (0..len).collect();
+    sorted.sort_unstable_by_key(|&index| entries[index as usize].0.as_bytes());
+    for index in sorted {
+        writer.write_all(&index.to_le_bytes())?;
+    }
+
+    for (key, value) in entries {
+        writer.write_all(key.as_bytes())?;
+        writer.write_all(value.get().as_bytes())?;
+    }
+    Ok(())
+}
+
+fn read_u32(bytes: &[u8], offset: usize) -> u32 {
+    let mut buf = [0; 4];
+    buf.copy_from_slice(&bytes[offset..offset + 4]);
+    u32::from_le_bytes(buf)
+}
@@ -0,0 +1,195 @@
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::Deref;
+
+use bumpalo::Bump;
+use hashbrown::DefaultHashBuilder;
+use serde::de::DeserializeSeed as _;
+use serde_json::value::RawValue;
+
+use crate::de::BumpStrSeed;
+use crate::value::Kind;
+use crate::{Error, RawMap};
+
+/// A [`RawMap`] memoizing the nested objects parsed and the strings unescaped from its values.
+///
+/// The memoized results are keyed by the address of the raw value they were computed from,
+/// so that they follow the values: if the value of a key is replaced, the new value is parsed or unescaped again.
+/// The results of the values replaced or removed through this map are evicted.
+pub struct MemoRawMap<'bump, S = DefaultHashBuilder> {
+    map: RawMap<'bump, S>,
+    /// The nested objects parsed by [`Self::get_object`].
+    objects: hashbrown::HashMap<*const RawValue, RawMap<'bump, S>, DefaultHashBuilder, &'bump Bump>,
+    /// The escaped strings unescaped by [`Self::get_str`].
+    strings: hashbrown::HashMap<*const RawValue, &'bump str, DefaultHashBuilder, &'bump Bump>,
+}
+
+impl<'bump, S> MemoRawMap<'bump, S> {
+    /// Memoizes the results computed from the values of an existing map.
+    #[inline]
+    pub fn from_map(map: RawMap<'bump, S>) -> Self {
+        let bump = map.bump();
+        Self {
+            map,
+            objects: hashbrown::HashMap::new_in(bump),
+            strings: hashbrown::HashMap::new_in(bump),
+        }
+    }
+
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the entries of the map.
+    #[inline]
+    pub fn as_slice(&self) -> &[(&'bump str, &'bump RawValue)] {
+        self.map.as_slice()
+    }
+
+    /// Returns the underlying map.
+    #[inline]
+    pub fn as_map(&self) -> &RawMap<'bump, S> {
+        &self.map
+    }
+
+    /// Consumes `self` and returns the underlying map, dropping the memoized results.
+    #[inline]
+    pub fn into_map(self) -> RawMap<'bump, S> {
+        self.map
+    }
+
+    /// Unescapes all the string values of the map into the bump, so that [`Self::get_str`] never has to.
+    ///
+    /// The values that are not strings are ignored.
+    pub fn unescape_strings(&mut self) {
+        for index in 0..self.map.len() {
+            let (_, value) = self.map.data[index];
+            if Kind::of(value) == Kind::String {
+                // raw values of the string kind are always valid strings
+                self.unescape(value).unwrap();
+            }
+        }
+    }
+
+    fn evict(&mut self, value: &RawValue) {
+        let value = value as *const RawValue;
+        self.objects.remove(&value);
+        self.strings.remove(&value);
+    }
+
+    fn unescape(&mut self, value: &'bump RawValue) -> Result<&'bump str, Error> {
+        let json = value.get();
+        if !json.contains('\\') {
+            return serde_json::from_str(json)
+                .map_err(|error| Error::from_raw_value(value, Kind::String, error));
+        }
+        let bump = self.map.bump();
+        match self.strings.entry(value as *const RawValue) {
+            hashbrown::hash_map::Entry::Occupied(entry) => Ok(entry.get()),
+            hashbrown::hash_map::Entry::Vacant(entry) => {
+                let string = BumpStrSeed(bump)
+                    .deserialize(value)
+                    .map_err(|error| Error::from_raw_value(value, Kind::String, error))?;
+                Ok(entry.insert(string))
+            }
+        }
+    }
+}
+
+impl<'bump, S: BuildHasher> MemoRawMap<'bump, S> {
+    /// Retrieves the value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
+        self.map.get(key)
+    }
+
+    /// Inserts a new (key, value) pair in the map, evicting the results memoized for the previous value.
+    ///
+    /// See [`RawMap::insert`].
+    #[inline]
+    pub fn insert(&mut self, key: &'bump str, value: &'bump RawValue) -> Option<&'bump RawValue> {
+        let previous = self.map.insert(key, value)?;
+        self.evict(previous);
+        Some(previous)
+    }
+
+    /// Removes the entry associated with a key by replacing it with the last entry,
+    /// evicting the results memoized for its value.
+    ///
+    /// See [`RawMap::swap_remove`].
+    #[inline]
+    pub fn swap_remove(&mut self, key: &str) -> Option<&'bump RawValue> {
+        let value = self.map.swap_remove(key)?;
+        self.evict(value);
+        Some(value)
+    }
+
+    /// Retrieves the value associated with a key and parses it as a nested map, if present.
+    ///
+    /// The nested map is allocated in the bump on first access and is memoized,
+    /// so that subsequent accesses to the same value do not parse it again.
+    ///
+    /// # Errors
+    ///
+    /// - if the value is not a JSON object. The path of the error is the key.
+    pub fn get_object(&mut self, key: &str) -> Result<Option<&RawMap<'bump, S>>, Error>
+    where
+        S: Clone,
+    {
+        let Some(value) = self.map.get(key) else {
+            return Ok(None);
+        };
+        let bump = self.map.bump();
+        match self.objects.entry(value as *const RawValue) {
+            hashbrown::hash_map::Entry::Occupied(entry) => Ok(Some(entry.into_mut())),
+            hashbrown::hash_map::Entry::Vacant(entry) => {
+                let hash_builder = self.map.cache.hasher().clone();
+                let object = RawMap::from_raw_value_and_hasher(value, hash_builder, bump)
+                    .map_err(|error| error.with_path(|| key.to_string()))?;
+                Ok(Some(entry.insert(object)))
+            }
+        }
+    }
+
+    /// Retrieves the value associated with a key as an unescaped string, if present.
+    ///
+    /// Strings without escape sequences are borrowed from the raw value. The other strings are unescaped
+    /// into the bump on first access and are memoized, so that subsequent accesses do not unescape them again.
+    ///
+    /// # Errors
+    ///
+    /// - if the value is not a JSON string. The path of the error is the key.
+    pub fn get_str(&mut self, key: &str) -> Result<Option<&'bump str>, Error> {
+        let Some(value) = self.map.get(key) else {
+            return Ok(None);
+        };
+        self.unescape(value)
+            .map(Some)
+            .map_err(|error| error.with_path(|| key.to_string()))
+    }
+}
+
+impl<'bump, S> Deref for MemoRawMap<'bump, S> {
+    type Target = [(&'bump str, &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<S> fmt::Debug for MemoRawMap<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MemoRawMap")
+            .field("map", self.as_map())
+            .finish()
+    }
+}
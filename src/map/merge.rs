@@ -0,0 +1,132 @@
+use std::hash::BuildHasher;
+
+use bumpalo::Bump;
+#[cfg(feature = "rayon")]
+use hashbrown::DefaultHashBuilder;
+use serde_json::value::RawValue;
+
+#[cfg(feature = "rayon")]
+use crate::map::FrozenRawMap;
+use crate::RawMap;
+
+impl<'bump, S: BuildHasher> RawMap<'bump, S> {
+    /// Inserts the entries of `entries` in the map, resolving the keys present in both with `conflict`.
+    ///
+    /// `conflict` receives the key, the current value and the new value, and returns the merged value.
+    /// It can allocate the merged value in the provided bump, which is the bump of this map.
+    pub fn merge_with<F>(&mut self, entries: &[(&'bump str, &'bump RawValue)], conflict: &F)
+    where
+        F: for<'x> Fn(&'x str, &'x RawValue, &'x RawValue, &'x Bump) -> &'x RawValue,
+    {
+        let bump = self.bump();
+        for &(key, value) in entries {
+            match self.get_index(key) {
+                Some(index) => {
+                    let current = self.data[index].1;
+                    self.data[index].1 = conflict(key, current, value, bump);
+                }
+                None => {
+                    self.insert(key, value);
+                }
+            }
+        }
+    }
+}
+
+/// The partial result of a subtree of the reduction of [`RawMap::par_merge`].
+#[cfg(feature = "rayon")]
+struct Partial<'b>(RawMap<'b, DefaultHashBuilder>);
+
+/// SAFETY:
+///
+/// - The partial result is allocated in the first bump of its subtree, and the subtrees reduced
+///   concurrently own disjoint bumps.
+/// - A partial result is only ever used by the task reducing its subtree, then by the task of its parent,
+///   once the former has completed.
+///
+/// So, the shared reference to the allocator is never used by two threads at the same time.
+#[cfg(feature = "rayon")]
+unsafe impl Send for Partial<'_> {}
+
+#[cfg(feature = "rayon")]
+impl<'b> Partial<'b> {
+    /// Merges the chunks in the first bump of `bumps`, splitting them in two subtrees reduced in parallel.
+    fn reduce<F>(
+        chunks: &[&[&[(&'b str, &'b RawValue)]]],
+        bumps: &'b mut [Bump],
+        conflict: &F,
+    ) -> Self
+    where
+        F: for<'x> Fn(&'x str, &'x RawValue, &'x RawValue, &'x Bump) -> &'x RawValue + Sync,
+    {
+        if chunks.len() <= 1 {
+            let bump: &'b Bump = &bumps[0];
+            let mut partial = RawMap::new_in(bump);
+            for entries in chunks.iter().flat_map(|chunk| chunk.iter()) {
+                partial.merge_with(entries, conflict);
+            }
+            return Partial(partial);
+        }
+
+        let (left_chunks, right_chunks) = chunks.split_at(chunks.len() / 2);
+        let (left_bumps, right_bumps) = bumps.split_at_mut(left_chunks.len());
+        let (Partial(mut left), Partial(right)) = rayon::join(
+            || Self::reduce(left_chunks, left_bumps, conflict),
+            || Self::reduce(right_chunks, right_bumps, conflict),
+        );
+        left.merge_with(right.as_slice(), conflict);
+        Partial(left)
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<'bump> RawMap<'bump, DefaultHashBuilder> {
+    /// Merges many frozen maps into a new map with a parallel reduction tree, on the current rayon thread pool.
+    ///
+    /// The maps are split in as many chunks of contiguous maps as there are bumps, and each chunk is first merged
+    /// by a task in its own bump. The partial results are then reduced pairwise: at each node of the tree,
+    /// the partial result of the right subtree is merged into the one of the left subtree, in the bump of the latter,
+    /// so that each bump is only ever allocated in by one task at a time.
+    /// The root of the tree is finally consolidated into a new map allocated in `bump`.
+    ///
+    /// The keys of the new map appear in the order of their first occurrence in `maps`,
+    /// and the keys present in multiple maps are resolved with `conflict`, as in [`Self::merge_with`].
+    /// As the partial results are merged in a tree, `conflict` must be associative.
+    ///
+    /// The merged values can be allocated in the bumps of the tasks, so the new map borrows `bumps`,
+    /// which must outlive it:
+    ///
+    /// ```compile_fail
+    /// # use bumpalo::Bump;
+    /// # use bumparaw_collections::RawMap;
+    /// let bump = Bump::new();
+    /// let mut bumps = vec![Bump::new(), Bump::new()];
+    /// let merged = RawMap::par_merge::<hashbrown::DefaultHashBuilder, _>(&[], |_, left, _, _| left, &mut bumps, &bump);
+    /// drop(bumps);
+    /// merged.len();
+    /// ```
+    ///
+    /// # Panics
+    ///
+    /// - if `bumps` is empty.
+    pub fn par_merge<'m: 'b, 'b: 'bump, S, F>(
+        maps: &[FrozenRawMap<'_, 'm, S>],
+        conflict: F,
+        bumps: &'b mut [Bump],
+        bump: &'bump Bump,
+    ) -> Self
+    where
+        F: for<'x> Fn(&'x str, &'x RawValue, &'x RawValue, &'x Bump) -> &'x RawValue + Sync,
+    {
+        assert!(!bumps.is_empty(), "at least one bump is required");
+
+        let slices: Vec<_> = maps.iter().map(|map| map.as_slice()).collect();
+        let chunk_len = slices.len().div_ceil(bumps.len()).max(1);
+        let chunks: Vec<_> = slices.chunks(chunk_len).collect();
+
+        let Partial(root) = Partial::reduce(&chunks, bumps, &conflict);
+        let mut merged = RawMap::new_in(bump);
+        merged.merge_with(root.as_slice(), &conflict);
+        merged
+    }
+}
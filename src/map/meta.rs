@@ -0,0 +1,195 @@
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::Deref;
+
+use bumpalo::collections::Vec as BVec;
+use bumpalo::Bump;
+use hashbrown::DefaultHashBuilder;
+use serde_json::value::RawValue;
+
+use crate::RawMap;
+
+/// A [`RawMap`] with a piece of metadata attached to each entry.
+///
+/// The metadata are stored in a side-table indexed by the position of the entries,
+/// which the map keeps in sync as entries are inserted and removed.
+/// This avoids a second map keyed by the same keys to hold per-entry state,
+/// such as field ids or seen flags.
+pub struct RawMapMeta<'bump, T, S = DefaultHashBuilder> {
+    map: RawMap<'bump, S>,
+    meta: BVec<'bump, T>,
+}
+
+impl<'bump, T> RawMapMeta<'bump, T, DefaultHashBuilder> {
+    /// Constructs an empty map backed by the specified bump allocator.
+    #[inline]
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        Self {
+            map: RawMap::new_in(bump),
+            meta: BVec::new_in(bump),
+        }
+    }
+}
+
+impl<'bump, T, S> RawMapMeta<'bump, T, S> {
+    /// Attaches metadata to the entries of an existing map, starting from the default metadata.
+    pub fn from_map(map: RawMap<'bump, S>) -> Self
+    where
+        T: Default,
+    {
+        let mut meta = BVec::with_capacity_in(map.len(), map.bump());
+        meta.extend(std::iter::repeat_with(T::default).take(map.len()));
+        Self { map, meta }
+    }
+
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the entries of the map.
+    #[inline]
+    pub fn as_slice(&self) -> &[(&'bump str, &'bump RawValue)] {
+        self.map.as_slice()
+    }
+
+    /// Returns the metadata of the entries, in the order of the entries.
+    #[inline]
+    pub fn metas(&self) -> &[T] {
+        &self.meta
+    }
+
+    /// Returns the metadata of the entries mutably, in the order of the entries.
+    #[inline]
+    pub fn metas_mut(&mut self) -> &mut [T] {
+        &mut self.meta
+    }
+
+    /// Iterates over the entries of the map along with their metadata.
+    pub fn iter(&self) -> impl Iterator<Item = (&'bump str, &'bump RawValue, &T)> {
+        self.map
+            .iter()
+            .zip(self.meta.iter())
+            .map(|((key, value), meta)| (key, value, meta))
+    }
+
+    /// Returns the underlying map.
+    #[inline]
+    pub fn as_map(&self) -> &RawMap<'bump, S> {
+        &self.map
+    }
+
+    /// Consumes `self` and returns the underlying map and the metadata of its entries.
+    #[inline]
+    pub fn into_parts(self) -> (RawMap<'bump, S>, BVec<'bump, T>) {
+        (self.map, self.meta)
+    }
+}
+
+impl<'bump, T, S: BuildHasher> RawMapMeta<'bump, T, S> {
+    /// Inserts a new (key, value) pair in the map, along with its metadata.
+    ///
+    /// If the key already exists, the value and the metadata are updated in place,
+    /// and the previous value and metadata are returned.
+    /// See [`RawMap::insert`].
+    #[inline]
+    pub fn insert(
+        &mut self,
+        key: &'bump str,
+        value: &'bump RawValue,
+        meta: T,
+    ) -> Option<(&'bump RawValue, T)> {
+        match self.map.get_index(key) {
+            Some(index) => {
+                let value = self.map.insert(key, value).unwrap();
+                Some((value, std::mem::replace(&mut self.meta[index], meta)))
+            }
+            None => {
+                self.map.insert(key, value);
+                self.meta.push(meta);
+                None
+            }
+        }
+    }
+
+    /// Removes the entry associated with a key by replacing it with the last entry,
+    /// and returns its value and metadata.
+    ///
+    /// The metadata of the last entry move along with it. See [`RawMap::swap_remove`].
+    #[inline]
+    pub fn swap_remove(&mut self, key: &str) -> Option<(&'bump RawValue, T)> {
+        let index = self.map.get_index(key)?;
+        let value = self.map.swap_remove(key)?;
+        Some((value, self.meta.swap_remove(index)))
+    }
+
+    /// Retains only the entries that satisfy the predicate, preserving their order.
+    ///
+    /// The metadata of the removed entries are dropped. The predicate is called once per entry, in order,
+    /// and the occurrences of a duplicated key are retained or removed independently, see [`RawMap::retain_keys`].
+    pub fn retain<F>(&mut self, mut keep: F)
+    where
+        F: FnMut(&'bump str, &'bump RawValue, &mut T) -> bool,
+    {
+        let meta = &mut self.meta;
+        let mut write = 0;
+        self.map.retain_entries(|read, key, value| {
+            let retained = keep(key, value, &mut meta[read]);
+            if retained {
+                // the metadata of the removed entries are moved after `write`
+                meta.swap(write, read);
+                write += 1;
+            }
+            retained
+        });
+        meta.truncate(write);
+    }
+
+    /// Retrieves the value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
+        self.map.get(key)
+    }
+
+    /// Retrieves the index of a key in the data slice, if present.
+    #[inline]
+    pub fn get_index(&self, key: &str) -> Option<usize> {
+        self.map.get_index(key)
+    }
+
+    /// Retrieves the metadata of the entry associated with a key, if present.
+    #[inline]
+    pub fn get_meta(&self, key: &str) -> Option<&T> {
+        self.meta.get(self.map.get_index(key)?)
+    }
+
+    /// Retrieves the metadata of the entry associated with a key mutably, if present.
+    #[inline]
+    pub fn get_meta_mut(&mut self, key: &str) -> Option<&mut T> {
+        self.meta.get_mut(self.map.get_index(key)?)
+    }
+}
+
+impl<'bump, T, S> Deref for RawMapMeta<'bump, T, S> {
+    type Target = [(&'bump str, &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<T: fmt::Debug, S> fmt::Debug for RawMapMeta<'_, T, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map()
+            .entries(self.iter().map(|(key, value, meta)| (key, (value, meta))))
+            .finish()
+    }
+}
@@ -0,0 +1,179 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::Deref;
+
+use bumpalo::Bump;
+use hashbrown::DefaultHashBuilder;
+use serde_json::value::RawValue;
+
+use crate::value::Kind;
+use crate::{Error, RawMap};
+
+/// A normalization applied to the keys of a [`NormalizedRawMap`].
+pub trait KeyNormalizer {
+    /// Returns the normalized form of `key`, borrowing it if it is already normalized.
+    fn normalize<'k>(&self, key: &'k str) -> Cow<'k, str>;
+}
+
+impl KeyNormalizer for fn(&str) -> Cow<'_, str> {
+    #[inline]
+    fn normalize<'k>(&self, key: &'k str) -> Cow<'k, str> {
+        self(key)
+    }
+}
+
+/// Normalizes the keys to lowercase.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Lowercase;
+
+impl KeyNormalizer for Lowercase {
+    #[inline]
+    fn normalize<'k>(&self, key: &'k str) -> Cow<'k, str> {
+        if key.chars().any(char::is_uppercase) {
+            Cow::Owned(key.to_lowercase())
+        } else {
+            Cow::Borrowed(key)
+        }
+    }
+}
+
+/// Removes the leading and trailing whitespace of the keys.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Trim;
+
+impl KeyNormalizer for Trim {
+    #[inline]
+    fn normalize<'k>(&self, key: &'k str) -> Cow<'k, str> {
+        Cow::Borrowed(key.trim())
+    }
+}
+
+/// Normalizes `key`, allocating the normalized form in `bump` if it differs from `key`.
+pub(crate) fn normalize_in<'bump>(
+    normalizer: &(impl KeyNormalizer + ?Sized),
+    key: &'bump str,
+    bump: &'bump Bump,
+) -> &'bump str {
+    match normalizer.normalize(key) {
+        Cow::Borrowed(key) => key,
+        Cow::Owned(key) => bump.alloc_str(&key),
+    }
+}
+
+/// A [`RawMap`] whose keys are normalized as they enter the map.
+///
+/// The lookups normalize the searched key the same way, so that a key is found
+/// whatever its form before normalization.
+pub struct NormalizedRawMap<'bump, N, S = DefaultHashBuilder> {
+    map: RawMap<'bump, S>,
+    normalizer: N,
+}
+
+impl<'bump, N: KeyNormalizer> NormalizedRawMap<'bump, N, DefaultHashBuilder> {
+    /// Constructs an empty map backed by the specified bump allocator.
+    #[inline]
+    pub fn new_in(normalizer: N, bump: &'bump Bump) -> Self {
+        Self {
+            map: RawMap::new_in(bump),
+            normalizer,
+        }
+    }
+
+    /// Constructs a map from a raw value and a bump allocator, normalizing the keys.
+    ///
+    /// If several keys have the same normalized form, the last value is kept at the position of the first key.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a map (JSON object).
+    #[inline]
+    pub fn from_raw_value(
+        raw: &'bump RawValue,
+        normalizer: N,
+        bump: &'bump Bump,
+    ) -> Result<Self, Error> {
+        let map = RawMap::from_deserializer_normalized(raw, &normalizer, Default::default(), bump)
+            .map_err(|error| Error::from_raw_value(raw, Kind::Object, error))?;
+        Ok(Self { map, normalizer })
+    }
+}
+
+impl<'bump, N: KeyNormalizer, S: BuildHasher> NormalizedRawMap<'bump, N, S> {
+    /// Inserts a new (key, value) pair in the map, normalizing the key.
+    ///
+    /// The normalized key is allocated in the bump if it differs from `key`.
+    /// See [`RawMap::insert`] for the behavior with existing keys.
+    #[inline]
+    pub fn insert(&mut self, key: &'bump str, value: &'bump RawValue) -> Option<&'bump RawValue> {
+        let key = normalize_in(&self.normalizer, key, self.map.bump());
+        self.map.insert(key, value)
+    }
+
+    /// Retrieves the value associated with the normalized form of a key, if present.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
+        self.map.get(&self.normalizer.normalize(key))
+    }
+
+    /// Retrieves the index of the normalized form of a key in the data slice, if present.
+    #[inline]
+    pub fn get_index(&self, key: &str) -> Option<usize> {
+        self.map.get_index(&self.normalizer.normalize(key))
+    }
+}
+
+impl<'bump, N, S> NormalizedRawMap<'bump, N, S> {
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the entries of the map, with normalized keys.
+    #[inline]
+    pub fn as_slice(&self) -> &[(&'bump str, &'bump RawValue)] {
+        self.map.as_slice()
+    }
+
+    /// Returns the underlying map, whose lookups do not normalize the keys.
+    #[inline]
+    pub fn as_map(&self) -> &RawMap<'bump, S> {
+        &self.map
+    }
+
+    /// Consumes `self` and returns the underlying map, whose lookups do not normalize the keys.
+    #[inline]
+    pub fn into_map(self) -> RawMap<'bump, S> {
+        self.map
+    }
+
+    /// Returns the normalizer applied to the keys.
+    #[inline]
+    pub fn normalizer(&self) -> &N {
+        &self.normalizer
+    }
+}
+
+impl<'bump, N, S> Deref for NormalizedRawMap<'bump, N, S> {
+    type Target = [(&'bump str, &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<N, S> fmt::Debug for NormalizedRawMap<'_, N, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("NormalizedRawMap")
+            .field("map", &self.map)
+            .finish()
+    }
+}
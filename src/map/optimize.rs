@@ -0,0 +1,60 @@
+use std::hash::BuildHasher;
+
+use hashbrown::DefaultHashBuilder;
+use serde_json::value::RawValue;
+
+use super::FrozenRawMap;
+
+impl<S: BuildHasher> FrozenRawMap<'_, '_, S> {
+    /// Builds an index of the keys optimized for maps that are queried many times,
+    /// such as settings or schemas.
+    ///
+    /// The index is a sorted array of the fingerprints of the keys: the lookups then hash the key once
+    /// and perform a binary search in a contiguous array instead of probing the hash table,
+    /// and never allocate. The lookups return the same entries as before the call.
+    ///
+    /// Building the index allocates on the heap and takes `O(n log n)` time.
+    pub fn optimize(&mut self) {
+        let hash_builder = DefaultHashBuilder::default();
+        let mut fingerprints: Vec<_> = self
+            .data
+            .iter()
+            .enumerate()
+            // only index the occurrence of the repeated keys that lookups return
+            .filter(|&(index, (key, _))| self.get_index(key) == Some(index))
+            .map(|(index, (key, _))| (hash_builder.hash_one(key), index))
+            .collect();
+        fingerprints.sort_unstable();
+        self.index = Some(FingerprintIndex {
+            hash_builder,
+            fingerprints: fingerprints.into_boxed_slice(),
+        });
+    }
+
+    /// `true` if [`Self::optimize`] was called on this map.
+    #[inline]
+    pub fn is_optimized(&self) -> bool {
+        self.index.is_some()
+    }
+}
+
+/// The sorted fingerprints of the keys of a [`FrozenRawMap`], built by [`FrozenRawMap::optimize`].
+pub(super) struct FingerprintIndex {
+    hash_builder: DefaultHashBuilder,
+    /// The hashes of the keys with the index of their entry, sorted.
+    fingerprints: Box<[(u64, usize)]>,
+}
+
+impl FingerprintIndex {
+    /// Retrieves the index of a key in `data`, comparing the keys whose fingerprint matches.
+    #[inline]
+    pub(super) fn get(&self, key: &str, data: &[(&str, &RawValue)]) -> Option<usize> {
+        let fingerprint = self.hash_builder.hash_one(key);
+        let start = self.fingerprints.partition_point(|&(f, _)| f < fingerprint);
+        self.fingerprints[start..]
+            .iter()
+            .take_while(|&&(f, _)| f == fingerprint)
+            .map(|&(_, index)| index)
+            .find(|&index| data[index].0 == key)
+    }
+}
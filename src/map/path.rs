@@ -0,0 +1,274 @@
+use std::borrow::Cow;
+use std::fmt;
+use std::hash::BuildHasher;
+use std::marker::PhantomData;
+
+use serde::de::{DeserializeSeed, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use serde::{Deserialize, Deserializer};
+use serde_json::value::RawValue;
+
+use super::FrozenRawMap;
+use crate::{Error, RawMap};
+
+impl<'bump, S: BuildHasher> RawMap<'bump, S> {
+    /// Resolves a path in the map and deserializes the value it points to, if present.
+    ///
+    /// The path is either dotted, with the indices of array elements between brackets (`doggos[0].name`),
+    /// or a JSON pointer (`/doggos/0/name`).
+    ///
+    /// Only the subtree on the path is visited: the sibling values are skipped without being parsed
+    /// into maps or arrays. Returns `None` if a segment of the path is absent, out of bounds,
+    /// or points inside a value that is neither an object nor an array.
+    ///
+    /// # Errors
+    ///
+    /// - if the value cannot be deserialized as a `T`, as [`Error::Deserialize`]. The path of the error is `path`.
+    pub fn deserialize_at<T>(&self, path: &str) -> Result<Option<T>, Error>
+    where
+        T: Deserialize<'bump>,
+    {
+        deserialize_at(path, |key| self.get(key))
+    }
+}
+
+impl<'bump, S: BuildHasher> FrozenRawMap<'_, 'bump, S> {
+    /// Resolves a path in the map and deserializes the value it points to, if present.
+    ///
+    /// See [`RawMap::deserialize_at`] for the syntax of the path.
+    ///
+    /// # Errors
+    ///
+    /// - if the value cannot be deserialized as a `T`, as [`Error::Deserialize`]. The path of the error is `path`.
+    pub fn deserialize_at<T>(&self, path: &str) -> Result<Option<T>, Error>
+    where
+        T: Deserialize<'bump>,
+    {
+        deserialize_at(path, |key| self.get(key))
+    }
+}
+
+fn deserialize_at<'bump, T>(
+    path: &str,
+    get: impl FnOnce(&str) -> Option<&'bump RawValue>,
+) -> Result<Option<T>, Error>
+where
+    T: Deserialize<'bump>,
+{
+    let segments = parse_path(path);
+    let Some((first, rest)) = segments.split_first() else {
+        return Ok(None);
+    };
+    let Some(value) = first.key.as_deref().and_then(get) else {
+        return Ok(None);
+    };
+    Navigate::new(rest)
+        .deserialize(value)
+        .map_err(|error| Error::from(error).with_path(|| path.to_owned()))
+}
+
+/// A step of a path, matching either a key of an object or an index of an array.
+struct Segment<'p> {
+    key: Option<Cow<'p, str>>,
+    index: Option<usize>,
+}
+
+fn parse_path(path: &str) -> Vec<Segment<'_>> {
+    match path.strip_prefix('/') {
+        // JSON pointers do not say whether a token is a key or an index
+        Some(pointer) => pointer
+            .split('/')
+            .map(|token| {
+                let key = if token.contains('~') {
+                    Cow::Owned(token.replace("~1", "/").replace("~0", "~"))
+                } else {
+                    Cow::Borrowed(token)
+                };
+                Segment {
+                    index: key.parse().ok(),
+                    key: Some(key),
+                }
+            })
+            .collect(),
+        None => {
+            let mut segments = Vec::new();
+            for part in path.split('.') {
+                let (key, mut indices) = match part.find('[') {
+                    Some(bracket) => part.split_at(bracket),
+                    None => (part, ""),
+                };
+                if !key.is_empty() {
+                    segments.push(Segment {
+                        key: Some(Cow::Borrowed(key)),
+                        index: None,
+                    });
+                }
+                while let Some((index, rest)) = indices
+                    .strip_prefix('[')
+                    .and_then(|indices| indices.split_once(']'))
+                {
+                    segments.push(Segment {
+                        key: None,
+                        index: index.parse().ok(),
+                    });
+                    indices = rest;
+                }
+            }
+            segments
+        }
+    }
+}
+
+/// Descends along the segments, deserializing the value at the end of the path as a `T`.
+struct Navigate<'s, 'p, T> {
+    segments: &'s [Segment<'p>],
+    _marker: PhantomData<T>,
+}
+
+impl<'s, 'p, T> Navigate<'s, 'p, T> {
+    fn new(segments: &'s [Segment<'p>]) -> Self {
+        Self {
+            segments,
+            _marker: PhantomData,
+        }
+    }
+}
+
+impl<'de, T: Deserialize<'de>> DeserializeSeed<'de> for Navigate<'_, '_, T> {
+    type Value = Option<T>;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        match self.segments.split_first() {
+            None => T::deserialize(deserializer).map(Some),
+            Some((segment, rest)) => deserializer.deserialize_any(NavigateVisitor {
+                segment,
+                rest,
+                _marker: PhantomData,
+            }),
+        }
+    }
+}
+
+struct NavigateVisitor<'s, 'p, T> {
+    segment: &'s Segment<'p>,
+    rest: &'s [Segment<'p>],
+    _marker: PhantomData<T>,
+}
+
+impl<'de, T: Deserialize<'de>> Visitor<'de> for NavigateVisitor<'_, '_, T> {
+    type Value = Option<T>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "any valid JSON value")
+    }
+
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: MapAccess<'de>,
+    {
+        // the last occurrence of a duplicated key wins, as in `RawMap::insert`
+        let mut found = None;
+        let key = self.segment.key.as_deref();
+        while let Some(matches) = map.next_key_seed(KeyMatch(key))? {
+            if matches {
+                found = map.next_value_seed(Navigate::new(self.rest))?;
+            } else {
+                map.next_value::<IgnoredAny>()?;
+            }
+        }
+        Ok(found)
+    }
+
+    fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+    where
+        A: SeqAccess<'de>,
+    {
+        let mut found = None;
+        let mut index = 0;
+        loop {
+            if self.segment.index == Some(index) {
+                match seq.next_element_seed(Navigate::new(self.rest))? {
+                    Some(value) => found = value,
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            index += 1;
+        }
+        Ok(found)
+    }
+
+    fn visit_bool<E>(self, _v: bool) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_i64<E>(self, _v: i64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_u64<E>(self, _v: u64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_f64<E>(self, _v: f64) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_str<E>(self, _v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+
+    fn visit_unit<E>(self) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(None)
+    }
+}
+
+/// Deserializes a key, returning whether it is equal to the expected key without allocating it.
+struct KeyMatch<'k>(Option<&'k str>);
+
+impl<'de> DeserializeSeed<'de> for KeyMatch<'_> {
+    type Value = bool;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        deserializer.deserialize_str(self)
+    }
+}
+
+impl Visitor<'_> for KeyMatch<'_> {
+    type Value = bool;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a string")
+    }
+
+    fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+    where
+        E: serde::de::Error,
+    {
+        Ok(self.0 == Some(v))
+    }
+}
@@ -0,0 +1,173 @@
+use std::fmt;
+use std::io;
+use std::ops::Deref;
+
+use bumpalo::Bump;
+use serde_json::value::RawValue;
+
+use super::{scan, RawMapMeta};
+use crate::value::Kind;
+use crate::{Error, RawMap};
+
+/// A [`RawMap`] that re-serializes the entries it did not modify exactly as they were written.
+///
+/// The values of a [`RawMap`] are always kept verbatim, but its keys are unescaped and serialized again,
+/// so that a key written `"café"` comes out as `"café"`. This map remembers how the key of each
+/// original entry was written, and [`Self::write_to`] emits it as-is until the entry is modified.
+///
+/// The entries holding numbers in a non-canonical form, such as exponent notation, are also recorded,
+/// so that callers can tell which values would change if they were parsed and serialized again.
+pub struct PreservingRawMap<'bump> {
+    map: RawMapMeta<'bump, Option<Original<'bump>>>,
+}
+
+/// How an original entry of a [`PreservingRawMap`] was written.
+#[derive(Debug, Clone, Copy)]
+struct Original<'bump> {
+    /// The key, including its quotes and escapes.
+    key: &'bump str,
+    non_canonical_number: bool,
+}
+
+impl<'bump> PreservingRawMap<'bump> {
+    /// Constructs a map from a raw value, recording how each entry was written.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a map (JSON object).
+    pub fn from_raw_value(raw: &'bump RawValue, bump: &'bump Bump) -> Result<Self, Error> {
+        let mut map = RawMapMeta::from_map(RawMap::from_raw_value(raw, bump)?);
+        let source = raw.get();
+        let entries = map.as_map().as_slice();
+        let originals: Vec<_> = entries
+            .iter()
+            .map(|&(_, value)| {
+                // the values of a map parsed from `raw` always point inside it
+                let span = scan::entry_span(value, source)?;
+                Some(Original {
+                    key: &source[span.key],
+                    non_canonical_number: Kind::of(value) == Kind::Number
+                        && !crate::value::is_canonical_number(value),
+                })
+            })
+            .collect();
+        map.metas_mut().copy_from_slice(&originals);
+        Ok(Self { map })
+    }
+
+    /// Inserts a new (key, value) pair in the map, marking the entry as modified.
+    ///
+    /// See [`RawMap::insert`].
+    #[inline]
+    pub fn insert(&mut self, key: &'bump str, value: &'bump RawValue) -> Option<&'bump RawValue> {
+        self.map.insert(key, value, None).map(|(value, _)| value)
+    }
+
+    /// Removes the entry associated with a key by replacing it with the last entry, and returns its value.
+    ///
+    /// See [`RawMap::swap_remove`].
+    #[inline]
+    pub fn swap_remove(&mut self, key: &str) -> Option<&'bump RawValue> {
+        self.map.swap_remove(key).map(|(value, _)| value)
+    }
+
+    /// Retrieves the value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
+        self.map.get(key)
+    }
+
+    /// `true` if the entry associated with a key was inserted or updated since the map was parsed.
+    ///
+    /// Returns `false` if the key is absent.
+    #[inline]
+    pub fn is_modified(&self, key: &str) -> bool {
+        self.map.get_meta(key).is_some_and(Option::is_none)
+    }
+
+    /// Iterates over the keys of the original entries whose value is a number in a non-canonical form.
+    ///
+    /// See [`crate::value::is_canonical_number`].
+    pub fn non_canonical_numbers(&self) -> impl Iterator<Item = &'bump str> + '_ {
+        self.map.iter().filter_map(|(key, _, original)| {
+            original
+                .filter(|original| original.non_canonical_number)
+                .map(|_| key)
+        })
+    }
+
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns the entries of the map, with unescaped keys.
+    #[inline]
+    pub fn as_slice(&self) -> &[(&'bump str, &'bump RawValue)] {
+        self.map.as_slice()
+    }
+
+    /// Returns the underlying map.
+    #[inline]
+    pub fn as_map(&self) -> &RawMap<'bump> {
+        self.map.as_map()
+    }
+
+    /// Writes the map as compact JSON, emitting the keys of the unmodified entries as they were written.
+    ///
+    /// The values are always written verbatim.
+    ///
+    /// # Errors
+    ///
+    /// - if writing fails.
+    pub fn write_to<W: io::Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(b"{")?;
+        for (index, (key, value, original)) in self.map.iter().enumerate() {
+            if index != 0 {
+                writer.write_all(b",")?;
+            }
+            match original {
+                Some(original) => writer.write_all(original.key.as_bytes())?,
+                None => serde_json::to_writer(&mut writer, key)?,
+            }
+            writer.write_all(b":")?;
+            writer.write_all(value.get().as_bytes())?;
+        }
+        writer.write_all(b"}")
+    }
+
+    /// Writes the map as compact JSON allocated in `bump`.
+    ///
+    /// See [`Self::write_to`].
+    pub fn to_raw_value_in<'b>(&self, bump: &'b Bump) -> &'b RawValue {
+        let mut json = bumpalo::collections::Vec::new_in(bump);
+        // writing to a vector cannot fail
+        self.write_to(&mut json).unwrap();
+        // the keys and values are valid JSON
+        serde_json::from_slice(json.into_bump_slice()).unwrap()
+    }
+}
+
+impl<'bump> Deref for PreservingRawMap<'bump> {
+    type Target = [(&'bump str, &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl fmt::Debug for PreservingRawMap<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("PreservingRawMap")
+            .field("map", self.as_map())
+            .finish()
+    }
+}
@@ -0,0 +1,202 @@
+use std::ops::Range;
+
+use serde::de::Error as _;
+use serde_json::value::RawValue;
+
+use crate::Error;
+
+/// A minimal scanner over the top level of a JSON object.
+///
+/// It does not validate the entries, it only finds the boundaries of their keys and values,
+/// so that a malformed value does not prevent reading the following entries.
+pub(crate) struct ObjectScanner<'a> {
+    input: &'a str,
+    pos: usize,
+    done: bool,
+}
+
+/// The byte ranges of the key and value of an entry in a JSON object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntrySpan {
+    /// The span of the key, including its quotes.
+    pub key: Range<usize>,
+    /// The span of the value, without surrounding whitespace.
+    pub value: Range<usize>,
+}
+
+impl<'a> ObjectScanner<'a> {
+    /// Starts scanning `input`, that must begin with a JSON object.
+    pub fn new(input: &'a str) -> Result<Self, Error> {
+        let mut scanner = Self {
+            input,
+            pos: 0,
+            done: false,
+        };
+        scanner.skip_whitespace();
+        scanner.expect(b'{')?;
+        scanner.skip_whitespace();
+        if scanner.peek() == Some(b'}') {
+            scanner.close()?;
+        }
+        Ok(scanner)
+    }
+
+    /// Returns the spans of the next entry, or `None` when the end of the object is reached.
+    ///
+    /// # Errors
+    ///
+    /// - if the structure of the object is broken. The scanner cannot resume after such an error.
+    pub fn next_entry(&mut self) -> Result<Option<EntrySpan>, Error> {
+        if self.done {
+            return Ok(None);
+        }
+        match self.scan_entry() {
+            Ok(span) => Ok(Some(span)),
+            Err(error) => {
+                self.done = true;
+                Err(error)
+            }
+        }
+    }
+
+    fn scan_entry(&mut self) -> Result<EntrySpan, Error> {
+        self.skip_whitespace();
+        let key = self.scan_string()?;
+        self.skip_whitespace();
+        self.expect(b':')?;
+        self.skip_whitespace();
+        let value = self.scan_value()?;
+        self.skip_whitespace();
+        match self.peek() {
+            Some(b',') => self.pos += 1,
+            Some(b'}') => self.close()?,
+            _ => return Err(self.error("expected `,` or `}`")),
+        }
+        Ok(EntrySpan { key, value })
+    }
+
+    /// Consumes the closing brace of the object, that must only be followed by whitespace.
+    fn close(&mut self) -> Result<(), Error> {
+        self.pos += 1;
+        self.done = true;
+        self.skip_whitespace();
+        match self.peek() {
+            Some(_) => Err(self.error("trailing characters")),
+            None => Ok(()),
+        }
+    }
+
+    fn scan_string(&mut self) -> Result<Range<usize>, Error> {
+        let start = self.pos;
+        self.expect(b'"')?;
+        let bytes = self.input.as_bytes();
+        while let Some(&byte) = bytes.get(self.pos) {
+            self.pos += 1;
+            match byte {
+                b'\\' => self.pos += 1,
+                b'"' => return Ok(start..self.pos),
+                _ => {}
+            }
+        }
+        Err(self.error("unterminated string"))
+    }
+
+    /// Finds the end of the value starting at the current position.
+    ///
+    /// The value ends on the first `,` or `}` that is outside of a string and a nested value.
+    fn scan_value(&mut self) -> Result<Range<usize>, Error> {
+        let start = self.pos;
+        let mut depth = 0usize;
+        while let Some(byte) = self.peek() {
+            match byte {
+                b'"' => {
+                    self.scan_string()?;
+                    continue;
+                }
+                b'[' | b'{' => depth += 1,
+                b',' | b'}' if depth == 0 => break,
+                b']' | b'}' => depth = depth.saturating_sub(1),
+                _ => {}
+            }
+            self.pos += 1;
+        }
+        if self.peek().is_none() {
+            return Err(self.error("unterminated object"));
+        }
+        let end = start + self.input[start..self.pos].trim_end().len();
+        if start == end {
+            return Err(self.error("expected a value"));
+        }
+        Ok(start..end)
+    }
+
+    fn expect(&mut self, expected: u8) -> Result<(), Error> {
+        if self.peek() == Some(expected) {
+            self.pos += 1;
+            Ok(())
+        } else {
+            Err(self.error(&format!("expected `{}`", expected as char)))
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        while let Some(b' ' | b'\n' | b'\r' | b'\t') = self.peek() {
+            self.pos += 1;
+        }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    fn error(&self, msg: &str) -> Error {
+        Error::Parse {
+            path: None,
+            offset: Some(self.pos),
+            source: serde_json::Error::custom(msg),
+        }
+    }
+}
+
+/// Finds the span of the entry whose value is `value` in the JSON object `source`.
+///
+/// Returns `None` if `value` does not reference the bytes of `source`.
+pub(crate) fn entry_span(value: &RawValue, source: &str) -> Option<EntrySpan> {
+    let value_start = (value.get().as_ptr() as usize).checked_sub(source.as_ptr() as usize)?;
+    let value_end = value_start + value.get().len();
+    if value_end > source.len() {
+        return None;
+    }
+
+    // walk back from the value: whitespace, colon, whitespace, closing quote of the key
+    let bytes = source.as_bytes();
+    let skip_whitespace = |mut i: usize| {
+        while i > 0 && bytes[i - 1].is_ascii_whitespace() {
+            i -= 1;
+        }
+        i
+    };
+    let colon = skip_whitespace(value_start).checked_sub(1)?;
+    let key_end = skip_whitespace(colon);
+    if bytes.get(colon) != Some(&b':') || key_end == 0 || bytes[key_end - 1] != b'"' {
+        return None;
+    }
+    // the opening quote of the key is the first one that is not escaped
+    let mut key_start = key_end - 1;
+    loop {
+        key_start = bytes[..key_start].iter().rposition(|&b| b == b'"')?;
+        let backslashes = bytes[..key_start]
+            .iter()
+            .rev()
+            .take_while(|&&b| b == b'\\')
+            .count();
+        if backslashes % 2 == 0 {
+            break;
+        }
+    }
+
+    Some(EntrySpan {
+        key: key_start..key_end,
+        value: value_start..value_end,
+    })
+}
@@ -0,0 +1,131 @@
+use std::fmt;
+use std::hash::BuildHasher;
+use std::ops::{Deref, Range};
+
+use serde_json::value::RawValue;
+
+use super::FrozenRawMap;
+
+impl<'a, 'bump, S> FrozenRawMap<'a, 'bump, S> {
+    /// Returns a view over the entries at the indices of `range`.
+    ///
+    /// The view shares the lookup cache of the map and can be shared between threads,
+    /// so that sub-tasks can each process a range of entries.
+    ///
+    /// # Panics
+    ///
+    /// - if `range` is out of bounds.
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> FrozenRawMapSlice<'_, 'a, 'bump, S> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {range:?} out of bounds for a map of length {}",
+            self.len()
+        );
+        FrozenRawMapSlice { map: self, range }
+    }
+}
+
+/// A read-only view over a contiguous range of entries of a [`FrozenRawMap`], returned by [`FrozenRawMap::slice`].
+///
+/// The indices are relative to the start of the range, and lookups ignore the keys outside of the range.
+pub struct FrozenRawMapSlice<'m, 'a, 'bump, S> {
+    map: &'m FrozenRawMap<'a, 'bump, S>,
+    range: Range<usize>,
+}
+
+impl<'bump, S: BuildHasher> FrozenRawMapSlice<'_, '_, 'bump, S> {
+    /// Retrieves the value associated with a key, if present in the range.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
+        self.get_key_value(key).map(|(_, value)| value)
+    }
+
+    /// Retrieves the index of a key relative to the start of the range, if present in the range.
+    #[inline]
+    pub fn get_index(&self, key: &str) -> Option<usize> {
+        let index = self.map.get_index(key)?;
+        self.range
+            .contains(&index)
+            .then(|| index - self.range.start)
+    }
+
+    /// Retrieves the key and value associated with a key, if present in the range.
+    #[inline]
+    pub fn get_key_value(&self, key: &str) -> Option<(&'bump str, &'bump RawValue)> {
+        let index = self.get_index(key)?;
+        self.as_slice().get(index).copied()
+    }
+}
+
+impl<'m, 'a, 'bump, S> FrozenRawMapSlice<'m, 'a, 'bump, S> {
+    /// The number of entries in the range.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    /// `true` if the range is empty.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// The indices of the entries of this view in the map.
+    #[inline]
+    pub fn range(&self) -> Range<usize> {
+        self.range.clone()
+    }
+
+    /// Returns the entries of the range.
+    #[inline]
+    pub fn as_slice(&self) -> &'a [(&'bump str, &'bump RawValue)] {
+        &self.map.as_slice()[self.range.clone()]
+    }
+
+    /// Returns a view over the entries at the indices of `range`, relative to the start of this view.
+    ///
+    /// # Panics
+    ///
+    /// - if `range` is out of bounds.
+    #[inline]
+    pub fn slice(&self, range: Range<usize>) -> FrozenRawMapSlice<'m, 'a, 'bump, S> {
+        assert!(
+            range.start <= range.end && range.end <= self.len(),
+            "range {range:?} out of bounds for a slice of length {}",
+            self.len()
+        );
+        FrozenRawMapSlice {
+            map: self.map,
+            range: self.range.start + range.start..self.range.start + range.end,
+        }
+    }
+}
+
+impl<S> Clone for FrozenRawMapSlice<'_, '_, '_, S> {
+    #[inline]
+    fn clone(&self) -> Self {
+        Self {
+            map: self.map,
+            range: self.range.clone(),
+        }
+    }
+}
+
+impl<'bump, S> Deref for FrozenRawMapSlice<'_, '_, 'bump, S> {
+    type Target = [(&'bump str, &'bump RawValue)];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.as_slice()
+    }
+}
+
+impl<S> fmt::Debug for FrozenRawMapSlice<'_, '_, '_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FrozenRawMapSlice")
+            .field("range", &self.range)
+            .field("data", &self.as_slice())
+            .finish()
+    }
+}
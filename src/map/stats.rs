@@ -0,0 +1,96 @@
+use serde_json::value::RawValue;
+
+use crate::map::FrozenRawMap;
+use crate::RawMap;
+
+/// Size statistics about the entries of a map, as returned by [`RawMap::stats`].
+#[derive(Debug, Clone, Copy)]
+pub struct MapStats<'a, 'bump> {
+    /// The number of entries in the map.
+    pub len: usize,
+    /// The sum of the byte lengths of the keys.
+    pub key_bytes: usize,
+    /// The sum of the byte lengths of the raw JSON values.
+    pub value_bytes: usize,
+    /// The entry with the largest raw JSON value, if the map is not empty.
+    pub largest: Option<(&'bump str, &'bump RawValue)>,
+    entries: &'a [(&'bump str, &'bump RawValue)],
+}
+
+impl<'a, 'bump> MapStats<'a, 'bump> {
+    fn new(entries: &'a [(&'bump str, &'bump RawValue)]) -> Self {
+        let mut stats = Self {
+            len: entries.len(),
+            key_bytes: 0,
+            value_bytes: 0,
+            largest: None,
+            entries,
+        };
+        for &(key, value) in entries {
+            stats.key_bytes += key.len();
+            stats.value_bytes += value.get().len();
+            if stats
+                .largest
+                .is_none_or(|(_, largest)| largest.get().len() < value.get().len())
+            {
+                stats.largest = Some((key, value));
+            }
+        }
+        stats
+    }
+
+    /// The maximum nesting depth of the map, where the map itself counts as one level.
+    ///
+    /// This is computed on each call by scanning the raw values.
+    pub fn max_depth(&self) -> usize {
+        1 + self
+            .entries
+            .iter()
+            .map(|(_, value)| raw_depth(value.get()))
+            .max()
+            .unwrap_or(0)
+    }
+}
+
+/// The maximum nesting depth of arrays and objects in a JSON text.
+fn raw_depth(json: &str) -> usize {
+    let mut depth = 0usize;
+    let mut max_depth = 0;
+    let mut in_string = false;
+    let mut escaped = false;
+    for byte in json.bytes() {
+        if in_string {
+            match byte {
+                _ if escaped => escaped = false,
+                b'\\' => escaped = true,
+                b'"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+        match byte {
+            b'"' => in_string = true,
+            b'[' | b'{' => {
+                depth += 1;
+                max_depth = max_depth.max(depth);
+            }
+            b']' | b'}' => depth = depth.saturating_sub(1),
+            _ => {}
+        }
+    }
+    max_depth
+}
+
+impl<'bump, S> RawMap<'bump, S> {
+    /// Computes size statistics about the entries of the map.
+    pub fn stats(&self) -> MapStats<'_, 'bump> {
+        MapStats::new(self.as_slice())
+    }
+}
+
+impl<'a, 'bump, S> FrozenRawMap<'a, 'bump, S> {
+    /// Computes size statistics about the entries of the map.
+    pub fn stats(&self) -> MapStats<'a, 'bump> {
+        MapStats::new(self.as_slice())
+    }
+}
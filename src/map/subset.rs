@@ -0,0 +1,132 @@
+use std::cmp::Ordering;
+use std::hash::BuildHasher;
+
+use bumpalo::Bump;
+use serde_json::value::RawValue;
+
+use crate::value::{cmp_number_or_string, Kind};
+use crate::{RawMap, RawVec};
+
+impl<S: BuildHasher> RawMap<'_, S> {
+    /// `true` if every entry of this map is present in `other` with a semantically equal value.
+    ///
+    /// See [`Self::find_subset_mismatch`] for how the values are compared.
+    pub fn is_subset_of<S2: BuildHasher>(&self, other: &RawMap<'_, S2>) -> bool {
+        self.find_subset_mismatch(other).is_none()
+    }
+
+    /// Returns the path of the first entry of this map that is absent from `other` or that has a different value.
+    ///
+    /// The values are compared semantically:
+    ///
+    /// - nested objects are compared recursively, and must be subsets of the corresponding objects;
+    /// - arrays must have the same length and equal elements, objects in arrays must be equal;
+    /// - numbers are compared numerically, integers exactly, and strings are compared after unescaping.
+    ///
+    /// The path is made of the keys separated by dots, and of the indices of array elements between brackets.
+    pub fn find_subset_mismatch<S2: BuildHasher>(&self, other: &RawMap<'_, S2>) -> Option<String> {
+        let bump = Bump::new();
+        compare_entries(self.as_slice(), other, &Comparison::Subset, &bump)
+            .err()
+            .map(|path| path.into_string())
+    }
+}
+
+/// `true` if the values are semantically equal, parsing the nested objects and arrays in `bump`.
+///
+/// See [`RawMap::find_subset_mismatch`] for how the values are compared,
+/// except that nested objects must be equal instead of subsets.
+pub(crate) fn semantically_equal(left: &RawValue, right: &RawValue, bump: &Bump) -> bool {
+    compare_values(left, right, &Comparison::Equal, bump).is_ok()
+}
+
+enum Comparison {
+    Subset,
+    Equal,
+}
+
+/// The path of a mismatch, built from the mismatching value up to the root.
+struct Path(Vec<String>);
+
+impl Path {
+    fn new() -> Self {
+        Path(Vec::new())
+    }
+
+    fn with(mut self, step: String) -> Self {
+        self.0.push(step);
+        self
+    }
+
+    fn into_string(self) -> String {
+        let mut path = String::new();
+        for step in self.0.into_iter().rev() {
+            if !path.is_empty() && !step.starts_with('[') {
+                path.push('.');
+            }
+            path.push_str(&step);
+        }
+        path
+    }
+}
+
+fn compare_entries<S: BuildHasher>(
+    entries: &[(&str, &RawValue)],
+    other: &RawMap<'_, S>,
+    comparison: &Comparison,
+    bump: &Bump,
+) -> Result<(), Path> {
+    for &(key, value) in entries {
+        let Some(other_value) = other.get(key) else {
+            return Err(Path::new().with(key.to_string()));
+        };
+        compare_values(value, other_value, comparison, bump)
+            .map_err(|path| path.with(key.to_string()))?;
+    }
+    match comparison {
+        Comparison::Equal if entries.len() != other.len() => {
+            let extra = other
+                .keys()
+                .find(|key| entries.iter().all(|(k, _)| k != key));
+            Err(Path::new().with(extra.unwrap_or_default().to_string()))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn compare_values(
+    left: &RawValue,
+    right: &RawValue,
+    comparison: &Comparison,
+    bump: &Bump,
+) -> Result<(), Path> {
+    // raw values of the object or array kind are always valid objects or arrays
+    match (Kind::of(left), Kind::of(right)) {
+        (Kind::Object, Kind::Object) => {
+            let left = RawMap::from_raw_value(left, bump).unwrap();
+            let right = RawMap::from_raw_value(right, bump).unwrap();
+            compare_entries(left.as_slice(), &right, comparison, bump)
+        }
+        (Kind::Array, Kind::Array) => {
+            let left = RawVec::from_raw_value(left, bump).unwrap();
+            let right = RawVec::from_raw_value(right, bump).unwrap();
+            for (index, (left, right)) in left.iter().zip(right.iter()).enumerate() {
+                compare_values(left, right, &Comparison::Equal, bump)
+                    .map_err(|path| path.with(format!("[{index}]")))?;
+            }
+            if left.len() == right.len() {
+                Ok(())
+            } else {
+                Err(Path::new().with(format!("[{}]", left.len().min(right.len()))))
+            }
+        }
+        // the numbers are compared numerically, the integers exactly
+        (left_kind, right_kind) if left_kind == right_kind => {
+            match cmp_number_or_string(left, right) {
+                Ordering::Equal => Ok(()),
+                _ => Err(Path::new()),
+            }
+        }
+        _ => Err(Path::new()),
+    }
+}
@@ -0,0 +1,429 @@
+use std::fmt;
+use std::io;
+use std::mem::ManuallyDrop;
+
+use bumpalo::collections::Vec as BVec;
+use bumpalo::Bump;
+
+use crate::map::FrozenRawMap;
+use crate::{Error, RawMap};
+
+#[cfg(feature = "tokio")]
+pub use stream::{AsyncReader, OwnedBatch};
+
+#[cfg(feature = "tokio")]
+mod stream;
+
+/// The error of a malformed line of a newline-delimited JSON (NDJSON) input.
+#[derive(Debug)]
+pub struct LineError {
+    /// The number of the malformed line, starting at 1.
+    pub line: usize,
+    /// The parsing error.
+    pub error: Error,
+}
+
+impl fmt::Display for LineError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "invalid document at line {}: {}", self.line, self.error)
+    }
+}
+
+impl std::error::Error for LineError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+/// Iterates over the non-blank lines of `input`, along with their line number starting at 1.
+pub fn lines(input: &str) -> impl Iterator<Item = (usize, &str)> {
+    input
+        .split('\n')
+        .enumerate()
+        .map(|(index, line)| (index + 1, line))
+        .filter(|(_, line)| !line.trim().is_empty())
+}
+
+/// Parses each non-blank line of `input` as a JSON object.
+///
+/// # Errors
+///
+/// - if a line cannot be parsed as a map (JSON object), reporting the first malformed line.
+pub fn parse<'bump>(
+    input: &'bump str,
+    bump: &'bump Bump,
+) -> Result<BVec<'bump, RawMap<'bump>>, LineError> {
+    let mut maps = BVec::new_in(bump);
+    for (line, document) in lines(input) {
+        let map = parse_line(document, bump).map_err(|error| LineError { line, error })?;
+        maps.push(map);
+    }
+    Ok(maps)
+}
+
+/// Parses the non-blank lines of `input` as JSON objects in parallel, on the current rayon thread pool.
+///
+/// The input is split in as many chunks of contiguous lines as there are bumps,
+/// and each chunk is parsed by a task of the pool, allocating in its own bump.
+/// The returned maps are frozen, and appear in the order of the input lines.
+///
+/// # Errors
+///
+/// - if a line cannot be parsed as a map (JSON object), reporting the first malformed line.
+///
+/// # Panics
+///
+/// - if `bumps` is empty.
+#[cfg(feature = "rayon")]
+pub fn par_parse<'b>(
+    input: &'b str,
+    bumps: &'b mut [Bump],
+) -> Result<Vec<FrozenRawMap<'b, 'b, hashbrown::DefaultHashBuilder>>, LineError> {
+    use rayon::iter::{
+        IndexedParallelIterator as _, IntoParallelIterator as _, ParallelIterator as _,
+    };
+
+    assert!(!bumps.is_empty(), "at least one bump is required");
+
+    let chunks = split_lines(input, bumps.len());
+    let results: Vec<_> = chunks
+        .into_par_iter()
+        .zip(bumps.into_par_iter())
+        .map(|((first_line, chunk), bump)| {
+            let bump: &'b Bump = bump;
+            let mut maps = Vec::new();
+            for (line, document) in lines(chunk) {
+                let line = first_line + line - 1;
+                let map = parse_line(document, bump).map_err(|error| LineError { line, error })?;
+                maps.push(bump.alloc(map).freeze());
+            }
+            Ok(maps)
+        })
+        .collect();
+
+    let mut maps = Vec::new();
+    for result in results {
+        maps.extend(result?);
+    }
+    Ok(maps)
+}
+
+/// Reads newline-delimited JSON (NDJSON) documents from a reader, in batches of whole lines.
+///
+/// Each batch is read directly into a bump provided by the caller, typically taken from a pool
+/// and reset once the batch is processed, so that the input is never staged in a heap buffer.
+/// Compressed inputs are decompressed directly into the bumps by the readers constructed
+/// with `Reader::from_gzip` and `Reader::from_zstd`, behind the `flate2` and `zstd` features.
+pub struct Reader<R> {
+    reader: R,
+    /// The start of the line that did not fit in the previous batch.
+    pending: Vec<u8>,
+    batches: Batches,
+    eof: bool,
+}
+
+impl<R: io::Read> Reader<R> {
+    /// Constructs a reader of NDJSON documents from `reader`.
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader,
+            pending: Vec::new(),
+            batches: Batches::default(),
+            eof: false,
+        }
+    }
+
+    /// Reads the next batch of documents into `bump`, or returns `None` at the end of the input.
+    ///
+    /// The input is read until at least `batch_len` bytes are in the batch, which then ends with the last
+    /// complete line read. The rest of the input is kept for the next batch.
+    /// A leading UTF-8 byte order mark is skipped.
+    ///
+    /// # Errors
+    ///
+    /// - if reading fails or the read bytes are not valid UTF-8, reported as an [`Error::Io`].
+    /// - if a line cannot be parsed as a map (JSON object), reporting the first malformed line.
+    pub fn read_batch<'bump>(
+        &mut self,
+        bump: &'bump Bump,
+        batch_len: usize,
+    ) -> Result<Option<BVec<'bump, RawMap<'bump>>>, LineError> {
+        if self.eof && self.pending.is_empty() {
+            return Ok(None);
+        }
+        let io_error = |error: io::Error| LineError {
+            line: self.batches.lines + 1,
+            error: error.into(),
+        };
+
+        let mut buffer = BVec::with_capacity_in(batch_len.max(self.pending.len()), bump);
+        buffer.extend_from_slice(&self.pending);
+        self.pending.clear();
+        let mut searched = 0;
+        let end = loop {
+            if buffer.len() >= batch_len {
+                if let Some(position) = buffer[searched..].iter().rposition(|&byte| byte == b'\n') {
+                    break searched + position + 1;
+                }
+                searched = buffer.len();
+            }
+            if self.eof {
+                break buffer.len();
+            }
+            let len = buffer.len();
+            buffer.resize(len + crate::io::READ_CHUNK_LEN, 0);
+            match self.reader.read(&mut buffer[len..]) {
+                Ok(read) => {
+                    buffer.truncate(len + read);
+                    self.eof = read == 0;
+                }
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => buffer.truncate(len),
+                Err(error) => return Err(io_error(error)),
+            }
+        };
+        self.pending.extend_from_slice(&buffer[end..]);
+        buffer.truncate(end);
+
+        let maps = self.batches.parse(buffer.into_bump_slice(), bump)?;
+        // the input ended with a newline or blank lines, there is no document left
+        if maps.is_empty() && self.eof && self.pending.is_empty() {
+            return Ok(None);
+        }
+        Ok(Some(maps))
+    }
+}
+
+#[cfg(feature = "flate2")]
+impl<R: io::Read> Reader<flate2::read::MultiGzDecoder<R>> {
+    /// Constructs a reader of NDJSON documents from a gzip-compressed `reader`.
+    ///
+    /// The input can be made of multiple concatenated gzip members, as produced by appending to a dump.
+    pub fn from_gzip(reader: R) -> Self {
+        Self::new(flate2::read::MultiGzDecoder::new(reader))
+    }
+}
+
+#[cfg(feature = "zstd")]
+impl<R: io::Read> Reader<zstd::Decoder<'static, io::BufReader<R>>> {
+    /// Constructs a reader of NDJSON documents from a zstd-compressed `reader`.
+    ///
+    /// # Errors
+    ///
+    /// - if the zstd decompression context cannot be created.
+    pub fn from_zstd(reader: R) -> io::Result<Self> {
+        zstd::Decoder::new(reader).map(Self::new)
+    }
+}
+
+/// Parses newline-delimited JSON (NDJSON) documents from chunks of input pushed by the caller.
+///
+/// This is the counterpart of [`Reader`] for inputs that are not available through [`io::Read`],
+/// such as the body of a request received by an async server: each chunk returned by
+/// `AsyncBufRead::fill_buf` is passed to [`Self::feed`] before being consumed,
+/// so that the whole body is never buffered. With the `tokio` feature, `AsyncReader` does so as a `Stream`.
+///
+/// The documents are parsed in the bump provided with each chunk. Only the partial trailing line
+/// of a chunk is kept on the heap until the next chunk completes it.
+#[derive(Debug, Default)]
+pub struct Feeder {
+    /// The start of the line that is not complete yet.
+    pending: Vec<u8>,
+    batches: Batches,
+}
+
+impl Feeder {
+    /// Constructs a parser that has not received any input yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses the lines completed by `chunk` into `bump`.
+    ///
+    /// A leading UTF-8 byte order mark in the first chunk is skipped.
+    ///
+    /// # Errors
+    ///
+    /// - if the completed lines are not valid UTF-8, reported as an [`Error::Io`].
+    /// - if a line cannot be parsed as a map (JSON object), reporting the first malformed line.
+    pub fn feed<'bump>(
+        &mut self,
+        chunk: &[u8],
+        bump: &'bump Bump,
+    ) -> Result<BVec<'bump, RawMap<'bump>>, LineError> {
+        let Some(position) = chunk.iter().rposition(|&byte| byte == b'\n') else {
+            self.pending.extend_from_slice(chunk);
+            return Ok(BVec::new_in(bump));
+        };
+        let (complete, rest) = chunk.split_at(position + 1);
+        let mut buffer = BVec::with_capacity_in(self.pending.len() + complete.len(), bump);
+        buffer.extend_from_slice(&self.pending);
+        buffer.extend_from_slice(complete);
+        self.pending.clear();
+        self.pending.extend_from_slice(rest);
+        self.batches.parse(buffer.into_bump_slice(), bump)
+    }
+
+    /// Parses the last line of the input into `bump`, if it does not end with a newline.
+    ///
+    /// # Errors
+    ///
+    /// - if the line is not valid UTF-8, reported as an [`Error::Io`].
+    /// - if the line cannot be parsed as a map (JSON object).
+    pub fn finish<'bump>(self, bump: &'bump Bump) -> Result<BVec<'bump, RawMap<'bump>>, LineError> {
+        let Self {
+            pending,
+            mut batches,
+        } = self;
+        batches.parse(bump.alloc_slice_copy(&pending), bump)
+    }
+}
+
+/// Parses the successive batches of lines of an input, keeping track of the line numbers.
+#[derive(Debug, Default)]
+struct Batches {
+    /// The number of lines in the previous batches.
+    lines: usize,
+    started: bool,
+}
+
+impl Batches {
+    fn parse<'bump>(
+        &mut self,
+        mut bytes: &'bump [u8],
+        bump: &'bump Bump,
+    ) -> Result<BVec<'bump, RawMap<'bump>>, LineError> {
+        let first_line = self.lines + 1;
+        if !self.started {
+            bytes = crate::io::strip_bom(bytes);
+            self.started = true;
+        }
+        let input = std::str::from_utf8(bytes).map_err(|error| LineError {
+            line: first_line,
+            error: io::Error::new(io::ErrorKind::InvalidData, error).into(),
+        })?;
+        self.lines += input.bytes().filter(|&byte| byte == b'\n').count();
+
+        parse(input, bump).map_err(|error| LineError {
+            line: first_line + error.line - 1,
+            error: error.error,
+        })
+    }
+}
+
+/// The size of the buffer above which [`Writer`] flushes it to the inner writer.
+const WRITER_BUFFER_LEN: usize = 64 * 1024;
+
+/// Writes documents as newline-delimited JSON (NDJSON).
+///
+/// The documents are serialized into a reused internal buffer,
+/// that is written to the inner writer once it grows large enough.
+///
+/// As with [`io::BufWriter`], the buffered documents are written when the writer is dropped,
+/// but the errors are then ignored. Call [`Self::flush`] or [`Self::into_inner`] to handle them.
+pub struct Writer<W: io::Write> {
+    writer: W,
+    buffer: Vec<u8>,
+}
+
+impl<W: io::Write> Writer<W> {
+    /// Constructs a writer of NDJSON documents into `writer`.
+    pub fn new(writer: W) -> Self {
+        Self {
+            writer,
+            buffer: Vec::with_capacity(WRITER_BUFFER_LEN),
+        }
+    }
+
+    /// Writes a document, followed by a newline.
+    ///
+    /// # Errors
+    ///
+    /// - if writing to the inner writer fails.
+    pub fn write_document<S>(&mut self, map: &FrozenRawMap<'_, '_, S>) -> io::Result<()> {
+        serde_json::to_writer(&mut self.buffer, map)?;
+        self.buffer.push(b'\n');
+        if self.buffer.len() >= WRITER_BUFFER_LEN {
+            self.flush_buffer()?;
+        }
+        Ok(())
+    }
+
+    /// Writes all the documents of `maps`, each followed by a newline.
+    ///
+    /// # Errors
+    ///
+    /// - if writing to the inner writer fails.
+    pub fn write_documents<'r, 'a: 'r, 'bump: 'a, S: 'a, I>(&mut self, maps: I) -> io::Result<()>
+    where
+        I: IntoIterator<Item = &'r FrozenRawMap<'a, 'bump, S>>,
+    {
+        for map in maps {
+            self.write_document(map)?;
+        }
+        Ok(())
+    }
+
+    /// Writes the buffered documents and flushes the inner writer.
+    ///
+    /// # Errors
+    ///
+    /// - if writing to the inner writer fails.
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.flush_buffer()?;
+        self.writer.flush()
+    }
+
+    /// Flushes the buffered documents and returns the inner writer.
+    ///
+    /// # Errors
+    ///
+    /// - if writing to the inner writer fails.
+    pub fn into_inner(mut self) -> io::Result<W> {
+        self.flush()?;
+        let mut this = ManuallyDrop::new(self);
+        drop(std::mem::take(&mut this.buffer));
+        // SAFETY: `this` is never dropped nor used again, so the writer is moved out exactly once.
+        Ok(unsafe { std::ptr::read(&this.writer) })
+    }
+
+    fn flush_buffer(&mut self) -> io::Result<()> {
+        self.writer.write_all(&self.buffer)?;
+        self.buffer.clear();
+        Ok(())
+    }
+}
+
+impl<W: io::Write> Drop for Writer<W> {
+    fn drop(&mut self) {
+        // errors cannot be reported from a destructor
+        let _ = self.flush_buffer();
+    }
+}
+
+fn parse_line<'bump>(document: &'bump str, bump: &'bump Bump) -> Result<RawMap<'bump>, Error> {
+    let raw = serde_json::from_str(document).map_err(|error| Error::from_input(document, error))?;
+    RawMap::from_raw_value(raw, bump)
+}
+
+/// Splits `input` in at most `count` chunks of whole lines, along with the number of their first line.
+#[cfg(feature = "rayon")]
+fn split_lines(input: &str, count: usize) -> Vec<(usize, &str)> {
+    let target_len = input.len().div_ceil(count).max(1);
+    let mut chunks = Vec::with_capacity(count);
+    let mut rest = input;
+    let mut first_line = 1;
+    while !rest.is_empty() {
+        let end = match rest.as_bytes().get(target_len..) {
+            Some(tail) if chunks.len() + 1 < count => tail
+                .iter()
+                .position(|&byte| byte == b'\n')
+                .map_or(rest.len(), |position| target_len + position + 1),
+            _ => rest.len(),
+        };
+        let (chunk, tail) = rest.split_at(end);
+        chunks.push((first_line, chunk));
+        first_line += chunk.bytes().filter(|&byte| byte == b'\n').count();
+        rest = tail;
+    }
+    chunks
+}
@@ -0,0 +1,184 @@
+use std::fmt;
+use std::mem::ManuallyDrop;
+use std::pin::Pin;
+use std::ptr::NonNull;
+use std::task::{ready, Context, Poll};
+
+use bumpalo::collections::Vec as BVec;
+use bumpalo::Bump;
+use futures_core::Stream;
+use tokio::io::AsyncBufRead;
+
+use crate::ndjson::{Feeder, LineError};
+use crate::RawMap;
+
+/// Reads newline-delimited JSON (NDJSON) documents from an [`AsyncBufRead`], as a [`Stream`] of batches.
+///
+/// Each chunk returned by `poll_fill_buf` is parsed with a [`Feeder`] into a bump taken from the pool
+/// of the reader, and the documents it completes are yielded as an [`OwnedBatch`] owning that bump.
+/// The size of the batches thus follows the capacity of the buffer of the inner reader,
+/// e.g., the capacity given to `tokio::io::BufReader::with_capacity`.
+///
+/// The bumps of the batches are returned to the pool with [`Self::recycle`], and new bumps are
+/// allocated when the pool is empty. The stream ends after the first error.
+pub struct AsyncReader<R> {
+    reader: R,
+    /// `None` once the input is exhausted or an error was returned.
+    feeder: Option<Feeder>,
+    bumps: Vec<Bump>,
+}
+
+impl<R: AsyncBufRead + Unpin> AsyncReader<R> {
+    /// Constructs a stream of batches of NDJSON documents from `reader`, with an empty pool of bumps.
+    pub fn new(reader: R) -> Self {
+        Self::with_bumps(reader, Vec::new())
+    }
+
+    /// Constructs a stream of batches of NDJSON documents from `reader`, taking the bumps of the batches from `bumps`.
+    pub fn with_bumps(reader: R, bumps: Vec<Bump>) -> Self {
+        Self {
+            reader,
+            feeder: Some(Feeder::new()),
+            bumps,
+        }
+    }
+
+    /// Resets the bump of a batch and returns it to the pool, to be reused by the next batches.
+    pub fn recycle(&mut self, batch: OwnedBatch) {
+        let mut bump = batch.into_bump();
+        bump.reset();
+        self.bumps.push(bump);
+    }
+}
+
+impl<R: AsyncBufRead + Unpin> Stream for AsyncReader<R> {
+    type Item = Result<OwnedBatch, LineError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            let Some(feeder) = this.feeder.as_mut() else {
+                return Poll::Ready(None);
+            };
+            let chunk = match ready!(Pin::new(&mut this.reader).poll_fill_buf(cx)) {
+                Ok(chunk) => chunk,
+                Err(error) => {
+                    let line = feeder.batches.lines + 1;
+                    this.feeder = None;
+                    return Poll::Ready(Some(Err(LineError {
+                        line,
+                        error: error.into(),
+                    })));
+                }
+            };
+
+            let len = chunk.len();
+            let mut batch = OwnedBatch::new(this.bumps.pop().unwrap_or_default());
+            let result = if len == 0 {
+                this.feeder.take().unwrap().finish(batch.bump())
+            } else {
+                feeder.feed(chunk, batch.bump())
+            };
+            Pin::new(&mut this.reader).consume(len);
+
+            match result {
+                Ok(maps) if !maps.is_empty() => {
+                    *batch.maps = maps;
+                    return Poll::Ready(Some(Ok(batch)));
+                }
+                Ok(_) => {
+                    this.recycle(batch);
+                    if len == 0 {
+                        return Poll::Ready(None);
+                    }
+                }
+                Err(error) => {
+                    this.feeder = None;
+                    this.recycle(batch);
+                    return Poll::Ready(Some(Err(error)));
+                }
+            }
+        }
+    }
+}
+
+/// A batch of documents yielded by [`AsyncReader`], owning the bump they are allocated in.
+///
+/// The documents are only accessed through shared references, so the batch can be sent to another thread,
+/// and its bump returned to the pool of the reader with [`AsyncReader::recycle`] once processed.
+pub struct OwnedBatch {
+    /// The documents, borrowing `bump`, and dropped before it.
+    maps: ManuallyDrop<BVec<'static, RawMap<'static>>>,
+    /// The owned bump, allocated with [`Box`].
+    bump: NonNull<Bump>,
+}
+
+/// SAFETY:
+///
+/// - The bump is owned by the batch, and is only referenced by the documents of the batch.
+/// - The batch is not `Sync`, and only gives shared references to the documents, that cannot outlive it.
+///
+/// So, the references to the bump are never used by two threads at the same time.
+unsafe impl Send for OwnedBatch {}
+
+impl OwnedBatch {
+    fn new(bump: Bump) -> Self {
+        let bump = NonNull::from(Box::leak(Box::new(bump)));
+        // SAFETY: the bump lives until the batch is dropped
+        let maps = BVec::new_in(unsafe { bump.as_ref() });
+        Self {
+            maps: ManuallyDrop::new(maps),
+            bump,
+        }
+    }
+
+    fn bump(&self) -> &'static Bump {
+        // SAFETY: the bump lives until the batch is dropped,
+        // and the references given to the documents do not escape the batch
+        unsafe { self.bump.as_ref() }
+    }
+
+    /// The documents of the batch, in the order of the input lines.
+    #[inline]
+    pub fn maps(&self) -> &[RawMap<'_>] {
+        &self.maps
+    }
+
+    /// The number of documents in the batch.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.maps.len()
+    }
+
+    /// `true` if there are no documents in the batch.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.maps.is_empty()
+    }
+
+    /// Drops the documents and returns the bump they were allocated in.
+    pub fn into_bump(self) -> Bump {
+        let mut this = ManuallyDrop::new(self);
+        // SAFETY: the documents are dropped before the bump, and the batch is never used again
+        unsafe {
+            ManuallyDrop::drop(&mut this.maps);
+            *Box::from_raw(this.bump.as_ptr())
+        }
+    }
+}
+
+impl Drop for OwnedBatch {
+    fn drop(&mut self) {
+        // SAFETY: the documents are dropped before the bump, and the batch is never used again
+        unsafe {
+            ManuallyDrop::drop(&mut self.maps);
+            drop(Box::from_raw(self.bump.as_ptr()));
+        }
+    }
+}
+
+impl fmt::Debug for OwnedBatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.maps()).finish()
+    }
+}
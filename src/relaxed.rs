@@ -0,0 +1,93 @@
+use bumpalo::collections::String as BString;
+use bumpalo::Bump;
+use serde::de::Error as _;
+
+use crate::{Error, RawMap};
+
+/// Converts a relaxed JSON document into strict JSON allocated in `bump`.
+///
+/// The relaxed dialect accepts line (`//`) and block (`/* */`) comments and trailing commas
+/// in objects and arrays. The comments are removed and the trailing commas are dropped,
+/// the rest of the document is copied as-is.
+///
+/// # Errors
+///
+/// - if a block comment is not terminated.
+pub fn to_strict_in<'bump>(input: &str, bump: &'bump Bump) -> Result<&'bump str, Error> {
+    let mut output = BString::with_capacity_in(input.len(), bump);
+    let mut rest = input;
+    while let Some(c) = rest.chars().next() {
+        match c {
+            '"' => {
+                let len = string_len(rest);
+                output.push_str(&rest[..len]);
+                rest = &rest[len..];
+                continue;
+            }
+            '/' if rest.starts_with("//") => {
+                rest = rest.find('\n').map_or("", |end| &rest[end..]);
+                continue;
+            }
+            '/' if rest.starts_with("/*") => match rest[2..].find("*/") {
+                Some(end) => {
+                    // replace the comment by a space to avoid joining the surrounding tokens
+                    output.push(' ');
+                    rest = &rest[2 + end + 2..];
+                    continue;
+                }
+                None => {
+                    return Err(Error::Parse {
+                        path: None,
+                        offset: Some(input.len() - rest.len()),
+                        source: serde_json::Error::custom("unterminated block comment"),
+                    })
+                }
+            },
+            '}' | ']' => {
+                let trimmed = output.trim_end();
+                // only a comma following a value is trailing, the others are left for the parser to reject
+                if let Some(before) = trimmed.strip_suffix(',') {
+                    if !before.trim_end().ends_with(['[', '{', ',']) {
+                        output.truncate(trimmed.len() - 1);
+                    }
+                }
+                output.push(c);
+            }
+            c => output.push(c),
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    Ok(output.into_bump_str())
+}
+
+/// The length of the string literal at the start of `input`, including the quotes.
+///
+/// An unterminated string spans the rest of the input.
+fn string_len(input: &str) -> usize {
+    let mut escaped = false;
+    for (i, b) in input.bytes().enumerate().skip(1) {
+        match b {
+            _ if escaped => escaped = false,
+            b'\\' => escaped = true,
+            b'"' => return i + 1,
+            _ => (),
+        }
+    }
+    input.len()
+}
+
+impl<'bump> RawMap<'bump> {
+    /// Constructs a map from a relaxed JSON object, accepting comments and trailing commas.
+    ///
+    /// The document is first converted to strict JSON in the bump, see [`to_strict_in`],
+    /// so that the raw values of the map are always strict JSON.
+    ///
+    /// # Errors
+    ///
+    /// - if a block comment is not terminated.
+    /// - if the strict document cannot be parsed as a map (JSON object).
+    pub fn from_relaxed_str(input: &str, bump: &'bump Bump) -> Result<Self, Error> {
+        let strict = to_strict_in(input, bump)?;
+        Self::from_raw_value(serde_json::from_str(strict)?, bump)
+    }
+}
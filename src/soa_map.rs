@@ -0,0 +1,306 @@
+use std::fmt;
+use std::hash::BuildHasher;
+
+use bumpalo::collections::Vec as BVec;
+use bumpalo::Bump;
+use hashbrown::DefaultHashBuilder;
+use serde::de::Visitor;
+use serde::ser::SerializeMap;
+use serde::{Deserializer, Serialize};
+use serde_json::value::RawValue;
+
+use crate::de::BumpStrSeed;
+use crate::map::FrozenMap;
+use crate::value::Kind;
+use crate::{Error, Freeze};
+
+/// An order-preserving map storing its keys and values in two parallel vectors.
+///
+/// This is the structure-of-arrays counterpart of [`crate::RawMap`]: scanning the keys
+/// with [`Self::keys`] does not touch the values, and the other way around.
+/// Iteration happens in the order of insertion, and the lookups are in constant time.
+///
+/// All allocations happen in the associated [`Bump`].
+pub struct SoaRawMap<'bump, S = DefaultHashBuilder> {
+    keys: BVec<'bump, &'bump str>,
+    values: BVec<'bump, &'bump RawValue>,
+    cache: hashbrown::HashMap<&'bump str, usize, S, &'bump Bump>,
+}
+
+impl<'bump> SoaRawMap<'bump, DefaultHashBuilder> {
+    /// Constructs a map from a raw value and a bump allocator.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a map (JSON object).
+    #[inline]
+    pub fn from_raw_value(raw: &'bump RawValue, bump: &'bump Bump) -> Result<Self, Error> {
+        Self::from_raw_value_and_hasher(raw, DefaultHashBuilder::default(), bump)
+    }
+
+    /// Constructs an empty map backed by the specified bump allocator.
+    #[inline]
+    pub fn new_in(bump: &'bump Bump) -> Self {
+        Self::with_hasher_in(DefaultHashBuilder::default(), bump)
+    }
+}
+
+impl<'bump, S: BuildHasher> SoaRawMap<'bump, S> {
+    /// Constructs a map from a raw value, a `HashBuilder` and a bump allocator.
+    ///
+    /// # Errors
+    ///
+    /// - if the raw value cannot be parsed as a map (JSON object).
+    pub fn from_raw_value_and_hasher(
+        raw: &'bump RawValue,
+        hash_builder: S,
+        bump: &'bump Bump,
+    ) -> Result<Self, Error> {
+        raw.deserialize_map(SoaRawMapVisitor { bump, hash_builder })
+            .map_err(|error| Error::from_raw_value(raw, Kind::Object, error))
+    }
+
+    /// Inserts a new (key, value) pair in the map.
+    ///
+    /// If the key already exists, then the order of the first insertion of the key is maintained, the value is updated,
+    /// and the previous value is returned.
+    #[inline]
+    pub fn insert(&mut self, key: &'bump str, value: &'bump RawValue) -> Option<&'bump RawValue> {
+        match self.cache.entry(key) {
+            hashbrown::hash_map::Entry::Occupied(entry) => {
+                Some(std::mem::replace(&mut self.values[*entry.get()], value))
+            }
+            hashbrown::hash_map::Entry::Vacant(entry) => {
+                entry.insert(self.keys.len());
+                self.keys.push(key);
+                self.values.push(value);
+                None
+            }
+        }
+    }
+
+    /// Retrieves the value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
+        let index = self.cache.get(key)?;
+        self.values.get(*index).copied()
+    }
+
+    /// Retrieves the index of a key in the keys and values slices, if present.
+    #[inline]
+    pub fn get_index(&self, key: &str) -> Option<usize> {
+        self.cache.get(key).copied()
+    }
+
+    /// Reserves capacity for at least additional more elements to be inserted in the map.
+    ///
+    /// # Panics
+    ///
+    /// - if the new capacity exceeds [`isize::MAX`].
+    #[inline]
+    pub fn reserve(&mut self, additional: usize) {
+        self.keys.reserve(additional);
+        self.values.reserve(additional);
+        self.cache.reserve(additional);
+    }
+}
+
+impl<'bump, S> SoaRawMap<'bump, S> {
+    /// Constructs an empty map backed by the specified bump allocator.
+    #[inline]
+    pub fn with_hasher_in(hash_builder: S, bump: &'bump Bump) -> Self {
+        Self {
+            keys: BVec::new_in(bump),
+            values: BVec::new_in(bump),
+            cache: hashbrown::HashMap::with_hasher_in(hash_builder, bump),
+        }
+    }
+
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the keys of the map in first-insertion order.
+    #[inline]
+    pub fn keys(&self) -> &[&'bump str] {
+        &self.keys
+    }
+
+    /// Returns the values of the map in first-insertion order.
+    #[inline]
+    pub fn values(&self) -> &[&'bump RawValue] {
+        &self.values
+    }
+
+    /// Iterates over the (key, value) pairs of the map in first-insertion order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&'bump str, &'bump RawValue)> + '_ {
+        self.keys.iter().copied().zip(self.values.iter().copied())
+    }
+
+    /// Consumes `self` and returns the keys and values as bump slices.
+    #[inline]
+    pub fn into_bump_slices(self) -> (&'bump [&'bump str], &'bump [&'bump RawValue]) {
+        (self.keys.into_bump_slice(), self.values.into_bump_slice())
+    }
+
+    /// Makes this map [`Send`] by forbidding any future modifications.
+    #[inline]
+    pub fn freeze(&mut self) -> FrozenSoaRawMap<'_, 'bump, S> {
+        FrozenSoaRawMap::new(self)
+    }
+
+    /// Returns a shared reference to the allocator backing this map.
+    #[inline]
+    pub fn bump(&self) -> &'bump Bump {
+        self.keys.bump()
+    }
+}
+
+impl<'a, 'bump, S: Send> Freeze for &'a mut SoaRawMap<'bump, S> {
+    type Frozen = FrozenSoaRawMap<'a, 'bump, S>;
+
+    #[inline]
+    fn freeze(self) -> Self::Frozen {
+        FrozenSoaRawMap::new(self)
+    }
+}
+
+impl<S> Serialize for SoaRawMap<'_, S> {
+    fn serialize<SE>(&self, serializer: SE) -> Result<SE::Ok, SE::Error>
+    where
+        SE: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<S> fmt::Debug for SoaRawMap<'_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+/// A view into a [`SoaRawMap`] that prevents insertions, but can be sent between threads safely.
+pub struct FrozenSoaRawMap<'a, 'bump, S> {
+    keys: &'a [&'bump str],
+    values: &'a [&'bump RawValue],
+    cache: FrozenMap<'a, 'bump, &'bump str, usize, S>,
+}
+
+impl<'a, 'bump, S> FrozenSoaRawMap<'a, 'bump, S> {
+    /// Makes the passed map [`Send`] by preventing any future modifications.
+    #[inline]
+    pub fn new(map: &'a mut SoaRawMap<'bump, S>) -> Self {
+        Self {
+            keys: map.keys.as_slice(),
+            values: map.values.as_slice(),
+            cache: FrozenMap::new(&mut map.cache),
+        }
+    }
+
+    /// The number of elements in the map.
+    #[inline]
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// `true` if there are no elements in the map.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Returns the keys of the map in first-insertion order.
+    #[inline]
+    pub fn keys(&self) -> &'a [&'bump str] {
+        self.keys
+    }
+
+    /// Returns the values of the map in first-insertion order.
+    #[inline]
+    pub fn values(&self) -> &'a [&'bump RawValue] {
+        self.values
+    }
+
+    /// Iterates over the (key, value) pairs of the map in first-insertion order.
+    #[inline]
+    pub fn iter(&self) -> impl Iterator<Item = (&'bump str, &'bump RawValue)> + 'a {
+        self.keys.iter().copied().zip(self.values.iter().copied())
+    }
+}
+
+impl<'bump, S: BuildHasher> FrozenSoaRawMap<'_, 'bump, S> {
+    /// Retrieves the value associated with a key, if present.
+    #[inline]
+    pub fn get(&self, key: &str) -> Option<&'bump RawValue> {
+        let index = self.cache.get(key)?;
+        self.values.get(*index).copied()
+    }
+
+    /// Retrieves the index of a key in the keys and values slices, if present.
+    #[inline]
+    pub fn get_index(&self, key: &str) -> Option<usize> {
+        self.cache.get(key).copied()
+    }
+}
+
+impl<S> Serialize for FrozenSoaRawMap<'_, '_, S> {
+    fn serialize<SE>(&self, serializer: SE) -> Result<SE::Ok, SE::Error>
+    where
+        SE: serde::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for (key, value) in self.iter() {
+            map.serialize_entry(key, value)?;
+        }
+        map.end()
+    }
+}
+
+impl<S> fmt::Debug for FrozenSoaRawMap<'_, '_, S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
+    }
+}
+
+struct SoaRawMapVisitor<'bump, S> {
+    bump: &'bump Bump,
+    hash_builder: S,
+}
+
+impl<'bump, S: BuildHasher> Visitor<'bump> for SoaRawMapVisitor<'bump, S> {
+    type Value = SoaRawMap<'bump, S>;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write!(formatter, "a map")
+    }
+
+    #[inline]
+    fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+    where
+        A: serde::de::MapAccess<'bump>,
+    {
+        let mut soa = SoaRawMap::with_hasher_in(self.hash_builder, self.bump);
+        if let Some(size_hint) = map.size_hint() {
+            soa.reserve(size_hint);
+        }
+        while let Some(key) = map.next_key_seed(BumpStrSeed(self.bump))? {
+            let value: &'bump RawValue = map.next_value()?;
+            soa.insert(key, value);
+        }
+        Ok(soa)
+    }
+}
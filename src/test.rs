@@ -84,3 +84,2008 @@ fn test_ref_map() {
 
     assert!(bump.try_borrow_mut().is_ok());
 }
+
+#[test]
+fn move_and_swap_entries() {
+    let s = r#"{"a":1,"b":2,"id":3,"c":4}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    map.move_index(2, 0);
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"id":3,"a":1,"b":2,"c":4}"#
+    );
+    assert_eq!(map.get_index("id"), Some(0));
+    assert_eq!(map.get_index("b"), Some(2));
+
+    map.swap_indices(1, 3);
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"id":3,"c":4,"b":2,"a":1}"#
+    );
+    assert_eq!(map.get_index("a"), Some(3));
+    assert_eq!(map.get("c").unwrap().get(), "4");
+}
+
+#[test]
+fn sort_by_value() {
+    let s = r#"{"a":"b","b":10,"c":"a ","d":-2.5,"e":null,"f":2}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    map.sort_by_value(crate::value::cmp_number_or_string);
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"d":-2.5,"f":2,"b":10,"c":"a ","a":"b","e":null}"#
+    );
+    assert_eq!(map.get_index("d"), Some(0));
+    assert_eq!(map.get("a").unwrap().get(), r#""b""#);
+
+    // the integers above 2^53 are compared exactly, even against floats
+    let s = r#"{"a":9007199254740993,"b":9007199254740992.0,"c":-9007199254740993,"d":9007199254740992,"e":1e400,"f":-9007199254740992}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+    map.sort_by_value(crate::value::cmp_number_or_string);
+    let keys: Vec<_> = map.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["c", "f", "b", "d", "a", "e"]);
+}
+
+#[test]
+fn deref_to_slice() {
+    let s = r#"{"a":1,"b":2,"c":3}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    assert_eq!(map.windows(2).count(), 2);
+    assert_eq!(map.first().unwrap().0, "a");
+
+    let frozen = map.freeze();
+    let entries: &[(&str, &serde_json::value::RawValue)] = frozen.as_ref();
+    assert_eq!(entries.len(), 3);
+    assert_eq!(frozen.binary_search_by(|(key, _)| key.cmp(&"b")), Ok(1));
+}
+
+#[test]
+fn all_errors() {
+    let s = r#"{"a": 1, "b": [1,}, "c\n": "ok", "d": tru, "e": {"f": [2]} }"#;
+    let bump = Bump::new();
+    let (map, errors) = RawMap::try_from_str_all_errors(s, &bump).unwrap();
+
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"a":1,"c\n":"ok","e":{"f": [2]}}"#
+    );
+    let keys: Vec<_> = errors.iter().map(|error| error.key).collect();
+    assert_eq!(keys, ["b", "d"]);
+
+    assert!(RawMap::try_from_str_all_errors(r#"{"a": 1"#, &bump).is_err());
+    assert!(RawMap::try_from_str_all_errors("[1]", &bump).is_err());
+
+    // only whitespace may follow the object, as with `serde_json::from_str`
+    let error = RawMap::try_from_str_all_errors(r#"{"a": 1} x"#, &bump).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid JSON at byte offset 9: trailing characters"
+    );
+    assert!(RawMap::try_from_str_all_errors("{}}", &bump).is_err());
+    let (map, errors) = RawMap::try_from_str_all_errors("{\"a\": 1}\n ", &bump).unwrap();
+    assert_eq!((map.len(), errors.len()), (1, 0));
+    let (map, errors) = RawMap::try_from_str_all_errors(" { } ", &bump).unwrap();
+    assert!(map.is_empty() && errors.is_empty());
+}
+
+#[test]
+fn parse_many() {
+    let s = r#"[{"id":1},{"id":2,"title":"hello"}]"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let maps = RawMap::parse_many(raw, &bump).unwrap();
+    assert_eq!(maps.len(), 2);
+    assert_eq!(maps[1].get("title").unwrap().get(), r#""hello""#);
+
+    let raw = serde_json::from_str(r#"[{"id":1},2]"#).unwrap();
+    match RawMap::parse_many(raw, &bump) {
+        Err(crate::Error::WrongKind { path, found, .. }) => {
+            assert_eq!(path.as_deref(), Some("[1]"));
+            assert_eq!(found, crate::value::Kind::Number);
+        }
+        otherwise => panic!("unexpected {otherwise:?}"),
+    }
+
+    let raw = serde_json::from_str(r#"{"id":1}"#).unwrap();
+    assert!(matches!(
+        RawMap::parse_many(raw, &bump),
+        Err(crate::Error::WrongKind {
+            expected: crate::value::Kind::Array,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn from_reader() {
+    let s = format!(r#"{{"id":1,"text":"{}"}}"#, "a".repeat(20_000));
+    let bump = Bump::new();
+    let map = RawMap::from_reader(s.as_bytes(), &bump).unwrap();
+    assert_eq!(map.get("id").unwrap().get(), "1");
+    assert_eq!(serde_json::to_string(&map).unwrap(), s);
+
+    assert!(RawMap::from_reader(&b"[1]"[..], &bump).is_err());
+    assert!(RawMap::from_reader(&b"{\"a\":\"\xff\"}"[..], &bump).is_err());
+}
+
+#[test]
+fn tolerant_decoding() {
+    let bump = Bump::new();
+    let map = RawMap::from_reader(&b"\xEF\xBB\xBF{\"id\":1}"[..], &bump).unwrap();
+    assert_eq!(map.get("id").unwrap().get(), "1");
+
+    let input =
+        b"\xEF\xBB\xBF{\"id\":1,\"title\":\"caf\xE9\",\"ok\":\"\xEF\xBF\xBD\",\"n\":{\"k\xff\":2}}";
+    let (map, affected) = RawMap::from_slice_lossy(input, &bump).unwrap();
+    assert_eq!(affected, ["title", "n"]);
+    assert_eq!(map.get("title").unwrap().get(), "\"caf\u{fffd}\"");
+    assert_eq!(map.get("ok").unwrap().get(), "\"\u{fffd}\"");
+
+    let (_, affected) = RawMap::from_slice_lossy(b"{\"id\":1}", &bump).unwrap();
+    assert!(affected.is_empty());
+}
+
+#[test]
+fn ndjson() {
+    let input: String = (0..1000)
+        .map(|i| format!("{{\"id\":{i},\"title\":\"doc {i}\"}}\n\n"))
+        .collect();
+    let bump = Bump::new();
+    let maps = crate::ndjson::parse(&input, &bump).unwrap();
+    assert_eq!(maps.len(), 1000);
+    assert_eq!(maps[42].get("id").unwrap().get(), "42");
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn ndjson_par_parse() {
+    let input: String = (0..1000)
+        .map(|i| format!("{{\"id\":{i},\"title\":\"doc {i}\"}}\n\n"))
+        .collect();
+    let mut bumps: Vec<_> = (0..4).map(|_| Bump::new()).collect();
+    let frozen = crate::ndjson::par_parse(&input, &mut bumps).unwrap();
+    assert_eq!(frozen.len(), 1000);
+    for (i, map) in frozen.iter().enumerate() {
+        assert_eq!(map.get("id").unwrap().get(), i.to_string());
+    }
+
+    let input = format!("{input}{{\"id\":\n");
+    let mut bumps: Vec<_> = (0..3).map(|_| Bump::new()).collect();
+    let error = crate::ndjson::par_parse(&input, &mut bumps).unwrap_err();
+    assert_eq!(error.line, 2001);
+}
+
+#[test]
+fn frozen_key_value() {
+    let s = r#"{"a":1,"b":2}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let frozen = map.freeze();
+
+    let lookup = String::from("b");
+    let (key, value) = frozen.get_key_value(&lookup).unwrap();
+    assert_eq!((key, value.get()), ("b", "2"));
+    assert!(std::ptr::eq(key, frozen.as_slice()[1].0));
+    assert!(frozen.get_key_value("c").is_none());
+    assert_eq!(frozen.keys().collect::<Vec<_>>(), ["a", "b"]);
+}
+
+#[test]
+fn bytes_layout() {
+    let s = r#"{"title":"hello","id":42,"tags":["a","b"],"émoji":null}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let mut bytes = Vec::new();
+    map.freeze().write_to(&mut bytes).unwrap();
+
+    let view = crate::map::RawMapBytes::from_bytes(&bytes).unwrap();
+    assert_eq!(view.len(), 4);
+    assert_eq!(view.get("id"), Some("42"));
+    assert_eq!(view.get("tags"), Some(r#"["a","b"]"#));
+    assert_eq!(view.get("émoji"), Some("null"));
+    assert_eq!(view.get("missing"), None);
+    assert_eq!(view.get_index("title"), Some(0));
+    let keys: Vec<_> = view.iter().map(|(key, _)| key).collect();
+    assert_eq!(keys, ["title", "id", "tags", "émoji"]);
+
+    let other_bump = Bump::new();
+    let decoded = view.to_raw_map_in(&other_bump).unwrap();
+    assert_eq!(serde_json::to_string(&decoded).unwrap(), s);
+    assert_eq!(decoded.get("id").unwrap().get(), "42");
+
+    assert!(crate::map::RawMapBytes::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+    assert!(crate::map::RawMapBytes::from_bytes(b"brm").is_err());
+
+    // SAFETY: the bytes were checked by `from_bytes` above
+    let unchecked = unsafe { crate::map::RawMapBytes::from_bytes_unchecked(&bytes) };
+    assert_eq!(unchecked.len(), 4);
+    assert_eq!(unchecked.get("émoji"), Some("null"));
+    assert!(unchecked.iter().eq(view.iter()));
+}
+
+#[cfg(feature = "heed")]
+#[test]
+fn heed_codec() {
+    use heed::{BytesDecode as _, BytesEncode as _};
+
+    use crate::map::RawMapCodec;
+
+    let raw = serde_json::from_str(r#"{"title":"hello","id":42}"#).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let frozen = map.freeze();
+
+    let bytes = RawMapCodec::bytes_encode(frozen.as_slice()).unwrap();
+    let mut expected = Vec::new();
+    frozen.write_to(&mut expected).unwrap();
+    assert_eq!(bytes, expected);
+
+    let view = RawMapCodec::bytes_decode(&bytes).unwrap();
+    assert_eq!(view.get("id"), Some("42"));
+    let other_bump = Bump::new();
+    let decoded = view.to_raw_map_in(&other_bump).unwrap();
+    assert_eq!(decoded.get("title").unwrap().get(), r#""hello""#);
+
+    assert!(RawMapCodec::bytes_decode(b"brm1").is_err());
+}
+
+#[test]
+fn shared_key_interner() {
+    let bump = Bump::new();
+    let mut interner = crate::interner::Interner::new_in(&bump);
+    let first = serde_json::from_str(r#"{"title":"a","id":1}"#).unwrap();
+    let second = serde_json::from_str(r#"{"id":2,"title":"b"}"#).unwrap();
+
+    let first = RawMap::from_raw_value_with_interner(first, &mut interner, &bump).unwrap();
+    let second = RawMap::from_raw_value_with_interner(second, &mut interner, &bump).unwrap();
+
+    let (first_key, _) = first[first.get_index("title").unwrap()];
+    let (second_key, _) = second[second.get_index("title").unwrap()];
+    assert!(std::ptr::eq(first_key, second_key));
+    assert_eq!(interner.as_set().len(), 2);
+}
+
+#[test]
+fn stats() {
+    let s = r#"{"id":1,"nested":{"a":[{"b":"[[[["}]},"text":"some long text"}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let stats = map.stats();
+    assert_eq!(stats.len, 3);
+    assert_eq!(stats.key_bytes, 12);
+    assert_eq!(stats.value_bytes, 1 + 20 + 16);
+    assert_eq!(stats.largest.unwrap().0, "nested");
+    assert_eq!(stats.max_depth(), 4);
+
+    assert_eq!(RawMap::new_in(&bump).stats().max_depth(), 1);
+}
+
+#[test]
+fn value_interner() {
+    let bump = Bump::new();
+    let mut interner = crate::interner::ValueInterner::new_in(&bump);
+
+    let mut values = Vec::new();
+    for i in 0..100 {
+        let json = format!(r#"{{"kind":"{}","flag":true}}"#, ["book", "movie"][i % 2]);
+        let raw: &serde_json::value::RawValue = serde_json::from_str(&json).unwrap();
+        let map = RawMap::from_raw_value(raw, &bump).unwrap();
+        values.push(interner.interned(map.get("kind").unwrap()));
+        interner.interned(map.get("flag").unwrap());
+    }
+    assert_eq!(interner.len(), 3);
+    assert!(std::ptr::eq(values[0], values[2]));
+    assert!(!std::ptr::eq(values[0], values[1]));
+    assert_eq!(values[1].get(), r#""movie""#);
+
+    let long = serde_json::value::to_raw_value(&"a".repeat(100)).unwrap();
+    assert_eq!(interner.interned(&long).get(), long.get());
+    assert!(interner.get(&long).is_none());
+}
+
+#[test]
+fn wrong_kind_error() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str("[1, 2]").unwrap();
+    let error = RawMap::from_raw_value(raw, &bump).unwrap_err();
+    assert_eq!(error.to_string(), "expected an object, found an array");
+
+    let error = RawMap::try_from_str_all_errors(r#"{"a": 1, "b" 2}"#, &bump).unwrap_err();
+    assert!(matches!(
+        error,
+        crate::Error::Parse {
+            offset: Some(13),
+            ..
+        }
+    ));
+
+    // the offsets of the errors of serde_json are computed from their line and column
+    let error = RawMap::from_reader("{\n  \"é\": x}".as_bytes(), &bump).unwrap_err();
+    assert!(matches!(
+        error,
+        crate::Error::Parse {
+            offset: Some(10),
+            ..
+        }
+    ));
+    let error = RawMap::from_reader(r#"{"a": 1"#.as_bytes(), &bump).unwrap_err();
+    assert!(matches!(
+        error,
+        crate::Error::Parse {
+            offset: Some(7),
+            ..
+        }
+    ));
+    assert_eq!(
+        crate::Error::DepthExceeded { max_depth: 64 }.to_string(),
+        "nesting depth exceeds the maximum of 64"
+    );
+}
+
+#[test]
+fn preserve_duplicates() {
+    let s = r#"{"a":1,"b":2,"a":3}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+    assert!(!map.has_duplicates());
+    assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"a":3,"b":2}"#);
+
+    let mut map = RawMap::from_raw_value_preserving_duplicates(raw, &bump).unwrap();
+    assert!(map.has_duplicates());
+    assert_eq!(map.len(), 3);
+    assert_eq!(serde_json::to_string(&map).unwrap(), s);
+    assert_eq!(map.get("a").unwrap().get(), "3");
+    assert_eq!(map.get_index("a"), Some(2));
+
+    let four = serde_json::from_str("4").unwrap();
+    assert_eq!(map.insert("a", four).unwrap().get(), "3");
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"a":1,"b":2,"a":4}"#
+    );
+}
+
+#[test]
+fn entry_ref() {
+    let bump = Bump::new();
+    let mut map = RawMap::new_in(&bump);
+    let one = serde_json::from_str("1").unwrap();
+    let two = serde_json::from_str("2").unwrap();
+
+    let key = String::from("count");
+    assert_eq!(map.entry_ref(&key).or_insert(one).get(), "1");
+    let allocated = bump.allocated_bytes();
+    *map.entry_ref(&key).or_insert(two) = two;
+    assert_eq!(bump.allocated_bytes(), allocated);
+    assert_eq!(map.get("count").unwrap().get(), "2");
+
+    match map.entry_ref("other") {
+        crate::map::EntryRef::Vacant(entry) => {
+            entry.insert(one);
+        }
+        crate::map::EntryRef::Occupied(_) => panic!("unexpected occupied entry"),
+    }
+    match map.entry_ref("count") {
+        crate::map::EntryRef::Occupied(mut entry) => {
+            assert_eq!(entry.index(), 0);
+            assert_eq!(entry.insert(one).get(), "2");
+        }
+        crate::map::EntryRef::Vacant(_) => panic!("unexpected vacant entry"),
+    }
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"count":1,"other":1}"#
+    );
+}
+
+#[test]
+fn insert_copied() {
+    let bump = Bump::new();
+    let mut map = RawMap::new_in(&bump);
+    {
+        let key = String::from("title");
+        let value = serde_json::value::to_raw_value("hello").unwrap();
+        assert!(map.insert_copied(&key, &value).is_none());
+        let value = serde_json::value::to_raw_value("world").unwrap();
+        assert_eq!(map.insert_copied(&key, &value).unwrap().get(), r#""hello""#);
+    }
+    assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"title":"world"}"#);
+}
+
+#[test]
+fn get_deserialize() {
+    let s = r#"{"id":42,"tags":["a","b"],"title":"hello"}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    assert_eq!(map.get_deserialize::<u32>("id").unwrap(), Some(42));
+    assert_eq!(map.get_deserialize::<&str>("title").unwrap(), Some("hello"));
+    assert_eq!(map.get_deserialize::<u32>("missing").unwrap(), None);
+    let error = map.get_deserialize::<u32>("title").unwrap_err();
+    assert!(error
+        .to_string()
+        .starts_with("cannot deserialize the value at `title`: invalid type"));
+
+    let frozen = map.freeze();
+    let tags: Vec<&str> = frozen.get_deserialize("tags").unwrap().unwrap();
+    assert_eq!(tags, ["a", "b"]);
+}
+
+#[test]
+fn project() {
+    let s = r#"{"id":1,"title":"hello","overview":"long","year":2000}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let (projection, missing) = map.project(["year", "unknown", "id", "year"]);
+    assert_eq!(
+        serde_json::to_string(&projection).unwrap(),
+        r#"{"id":1,"year":2000}"#
+    );
+    assert_eq!(missing, ["unknown"]);
+    assert_eq!(projection.get_index("year"), Some(1));
+}
+
+#[test]
+fn retain_keys() {
+    let s = r#"{"_id":1,"title":"hello","_internal":true,"year":2000}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    map.retain_keys(|key| !key.starts_with('_'));
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"title":"hello","year":2000}"#
+    );
+    assert_eq!(map.get_index("year"), Some(1));
+    assert!(map.get("_id").is_none());
+
+    // the occurrences of a duplicated key are retained independently
+    let one = serde_json::from_str("1").unwrap();
+    let two = serde_json::from_str("2").unwrap();
+    for retained in [
+        [false, true, true],
+        [true, true, false],
+        [false, true, false],
+    ] {
+        let mut map = RawMap::new_in(&bump);
+        map.push("a", one);
+        map.push("b", one);
+        map.push("a", two);
+        let mut predicate = retained.into_iter();
+        map.retain_keys(|_| predicate.next().unwrap());
+        let expected = match retained {
+            [false, true, true] => (r#"{"b":1,"a":2}"#, Some(1)),
+            [true, true, false] => (r#"{"a":1,"b":1}"#, Some(0)),
+            _ => (r#"{"b":1}"#, None),
+        };
+        assert_eq!(serde_json::to_string(&map).unwrap(), expected.0);
+        assert_eq!(map.get_index("a"), expected.1);
+        assert_eq!(map.get_index("b"), Some(expected.1.map_or(0, |a| 1 - a)));
+    }
+}
+
+#[cfg(feature = "rayon")]
+#[test]
+fn par_merge() {
+    let sources: Vec<String> = (0..100)
+        .map(|i| format!(r#"{{"doc{}":1,"all":1}}"#, i % 10))
+        .collect();
+    let bump = Bump::new();
+    let mut maps: Vec<_> = sources
+        .iter()
+        .map(|s| RawMap::from_raw_value(serde_json::from_str(s).unwrap(), &bump).unwrap())
+        .collect();
+    let frozen: Vec<_> = maps.iter_mut().map(|map| map.freeze()).collect();
+
+    use serde_json::value::RawValue;
+    fn sum<'x>(
+        _key: &'x str,
+        left: &'x RawValue,
+        right: &'x RawValue,
+        bump: &'x Bump,
+    ) -> &'x RawValue {
+        let left: u64 = serde_json::from_str(left.get()).unwrap();
+        let right: u64 = serde_json::from_str(right.get()).unwrap();
+        let sum = bump.alloc_str(&(left + right).to_string());
+        serde_json::from_str(sum).unwrap()
+    }
+    let serial_bump = Bump::new();
+    let mut serial = RawMap::new_in(&serial_bump);
+    for map in &frozen {
+        serial.merge_with(map.as_slice(), &sum);
+    }
+
+    // an uneven number of threads, so that some levels of the tree have no right neighbor
+    for threads in [1, 4, 7] {
+        let mut bumps: Vec<_> = (0..threads).map(|_| Bump::new()).collect();
+        let merged_bump = Bump::new();
+        let merged = RawMap::par_merge(&frozen, sum, &mut bumps, &merged_bump);
+
+        assert_eq!(merged.len(), 11);
+        assert_eq!(merged.as_slice()[0].0, "doc0");
+        assert_eq!(merged.as_slice()[1].0, "all");
+        assert_eq!(merged.get("all").unwrap().get(), "100");
+        assert_eq!(merged.get("doc3").unwrap().get(), "10");
+        assert_eq!(format!("{merged:?}"), format!("{serial:?}"));
+    }
+}
+
+#[test]
+fn ndjson_writer() {
+    let input: String = (0..3000)
+        .map(|i| format!("{{\"id\":{i},\"title\":\"doc {i}\"}}\n"))
+        .collect();
+    let bump = Bump::new();
+    let mut maps = crate::ndjson::parse(&input, &bump).unwrap();
+    let frozen: Vec<_> = maps.iter_mut().map(|map| map.freeze()).collect();
+
+    let mut writer = crate::ndjson::Writer::new(Vec::new());
+    writer.write_documents(&frozen).unwrap();
+    let output = writer.into_inner().unwrap();
+    assert_eq!(String::from_utf8(output).unwrap(), input);
+
+    // the documents still in the buffer are written on drop
+    let mut output = Vec::new();
+    let mut writer = crate::ndjson::Writer::new(&mut output);
+    writer.write_documents(&frozen[..2]).unwrap();
+    drop(writer);
+    assert_eq!(
+        String::from_utf8(output).unwrap(),
+        "{\"id\":0,\"title\":\"doc 0\"}\n{\"id\":1,\"title\":\"doc 1\"}\n"
+    );
+}
+
+#[test]
+fn ndjson_reader() {
+    let input: String = std::iter::once("\u{feff}".to_owned())
+        .chain((0..3000).map(|i| format!("{{\"id\":{i}}}\n")))
+        .collect();
+    let mut reader = crate::ndjson::Reader::new(input.as_bytes());
+    let mut bump = Bump::new();
+    let mut ids = Vec::new();
+    let mut batches = 0;
+    loop {
+        let Some(maps) = reader.read_batch(&bump, 1000).unwrap() else {
+            break;
+        };
+        ids.extend(
+            maps.iter()
+                .map(|map| map.get("id").unwrap().get().to_owned()),
+        );
+        batches += 1;
+        drop(maps);
+        bump.reset();
+    }
+    // the batches end at the read chunk boundaries, the last one is not followed by an empty batch
+    assert_eq!(batches, 5);
+    let expected: Vec<_> = (0..3000).map(|i| i.to_string()).collect();
+    assert_eq!(ids, expected);
+
+    // the reads stop at the end of each part of the chain
+    let input = std::io::Read::chain(
+        "{\"id\":0}\n{\"id\"".as_bytes(),
+        ":1}\n{\"id\":}\n".as_bytes(),
+    );
+    let mut reader = crate::ndjson::Reader::new(input);
+    let bump = Bump::new();
+    assert_eq!(reader.read_batch(&bump, 8).unwrap().unwrap().len(), 1);
+    assert_eq!(reader.read_batch(&bump, 8).unwrap_err().line, 3);
+
+    let mut reader = crate::ndjson::Reader::new("{\"id\":0}\n\n".as_bytes());
+    assert_eq!(reader.read_batch(&bump, 1000).unwrap().unwrap().len(), 1);
+    assert!(reader.read_batch(&bump, 1000).unwrap().is_none());
+}
+
+#[cfg(feature = "flate2")]
+#[test]
+fn gzip_inputs() {
+    use std::io::Write as _;
+
+    let gzip = |input: &str| {
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(input.as_bytes()).unwrap();
+        encoder.finish().unwrap()
+    };
+
+    // the members of the gzip input are concatenated
+    let mut compressed = gzip("{\"id\":0}\n{\"id\"");
+    compressed.extend(gzip(":1}\n{\"id\":2}"));
+    let mut reader = crate::ndjson::Reader::from_gzip(compressed.as_slice());
+    let bump = Bump::new();
+    let maps = reader.read_batch(&bump, 1000).unwrap().unwrap();
+    assert_eq!(maps.len(), 3);
+    assert_eq!(maps[1].get("id").unwrap().get(), "1");
+    assert!(reader.read_batch(&bump, 1000).unwrap().is_none());
+
+    let compressed = gzip(r#"{"title": "hello"}"#);
+    let map = RawMap::from_gzip_reader(compressed.as_slice(), &bump).unwrap();
+    assert_eq!(map.get("title").unwrap().get(), r#""hello""#);
+    let error = RawMap::from_gzip_reader(&b"{}"[..], &bump).unwrap_err();
+    assert!(matches!(error, crate::Error::Io(_)), "{error}");
+}
+
+#[cfg(feature = "zstd")]
+#[test]
+fn zstd_inputs() {
+    let input: String = (0..3000).map(|i| format!("{{\"id\":{i}}}\n")).collect();
+    let compressed = zstd::encode_all(input.as_bytes(), 0).unwrap();
+    let mut reader = crate::ndjson::Reader::from_zstd(compressed.as_slice()).unwrap();
+    let mut bump = Bump::new();
+    let mut count = 0;
+    loop {
+        let Some(maps) = reader.read_batch(&bump, 1000).unwrap() else {
+            break;
+        };
+        count += maps.len();
+        drop(maps);
+        bump.reset();
+    }
+    assert_eq!(count, 3000);
+
+    let compressed = zstd::encode_all(&br#"{"title": "hello"}"#[..], 0).unwrap();
+    let map = RawMap::from_zstd_reader(compressed.as_slice(), &bump).unwrap();
+    assert_eq!(map.get("title").unwrap().get(), r#""hello""#);
+    let error = RawMap::from_zstd_reader(&b"{}"[..], &bump).unwrap_err();
+    assert!(matches!(error, crate::Error::Io(_)), "{error}");
+}
+
+#[test]
+fn ndjson_feeder() {
+    let input: String = std::iter::once("\u{feff}".to_owned())
+        .chain((0..300).map(|i| format!("{{\"id\":{i}}}\n")))
+        .chain(std::iter::once("{\"id\":300}".to_owned()))
+        .collect();
+    let mut feeder = crate::ndjson::Feeder::new();
+    let mut ids = Vec::new();
+    let mut bump = Bump::new();
+    for chunk in input.as_bytes().chunks(7) {
+        let maps = feeder.feed(chunk, &bump).unwrap();
+        ids.extend(
+            maps.iter()
+                .map(|map| map.get("id").unwrap().get().to_owned()),
+        );
+        drop(maps);
+        bump.reset();
+    }
+    let maps = feeder.finish(&bump).unwrap();
+    ids.extend(
+        maps.iter()
+            .map(|map| map.get("id").unwrap().get().to_owned()),
+    );
+    let expected: Vec<_> = (0..=300).map(|i| i.to_string()).collect();
+    assert_eq!(ids, expected);
+
+    let mut feeder = crate::ndjson::Feeder::new();
+    let bump = Bump::new();
+    assert_eq!(feeder.feed(b"{\"id\":0}\n{\"id", &bump).unwrap().len(), 1);
+    assert_eq!(feeder.feed(b"\":1}\n{", &bump).unwrap().len(), 1);
+    assert_eq!(feeder.finish(&bump).unwrap_err().line, 3);
+}
+
+#[cfg(feature = "tokio")]
+#[test]
+fn ndjson_async_reader() {
+    use futures_core::Stream;
+
+    async fn next<S: Stream + Unpin>(stream: &mut S) -> Option<S::Item> {
+        std::future::poll_fn(|cx| std::pin::Pin::new(&mut *stream).poll_next(cx)).await
+    }
+
+    let input: String = std::iter::once("\u{feff}".to_owned())
+        .chain((0..300).map(|i| format!("{{\"id\":{i}}}\n")))
+        .chain(std::iter::once("{\"id\":300}".to_owned()))
+        .collect();
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .build()
+        .unwrap();
+    runtime.block_on(async {
+        let reader = tokio::io::BufReader::with_capacity(64, input.as_bytes());
+        let mut stream = crate::ndjson::AsyncReader::new(reader);
+        let mut ids = Vec::new();
+        let mut batches = 0;
+        while let Some(batch) = next(&mut stream).await {
+            let batch = batch.unwrap();
+            // the batches can be processed on another thread
+            let batch = std::thread::spawn(move || batch).join().unwrap();
+            ids.extend(
+                batch
+                    .maps()
+                    .iter()
+                    .map(|map| map.get("id").unwrap().get().to_owned()),
+            );
+            batches += 1;
+            stream.recycle(batch);
+        }
+        assert!(batches > 1);
+        let expected: Vec<_> = (0..=300).map(|i| i.to_string()).collect();
+        assert_eq!(ids, expected);
+
+        let input = "{\"id\":0}\n{\"id\":}\n{\"id\":2}\n";
+        let mut stream = crate::ndjson::AsyncReader::new(input.as_bytes());
+        assert_eq!(next(&mut stream).await.unwrap().unwrap_err().line, 2);
+        assert!(next(&mut stream).await.is_none());
+    });
+}
+
+#[test]
+fn bytes_map() {
+    let bump = Bump::new();
+    let mut map = crate::RawBytesMap::new_in(&bump);
+    let one = serde_json::from_str("1").unwrap();
+    let two = serde_json::from_str("2").unwrap();
+
+    map.insert(&[0xff, 0x00], one);
+    map.insert(b"key", two);
+    assert_eq!(map.insert(&[0xff, 0x00], two).unwrap().get(), "1");
+    assert_eq!(map.len(), 2);
+
+    let frozen = map.freeze();
+    assert_eq!(frozen.get(&[0xff, 0x00]).unwrap().get(), "2");
+    assert_eq!(frozen.get_index(b"key"), Some(1));
+    assert!(frozen.get(b"missing").is_none());
+}
+
+#[test]
+fn compact_into() {
+    let bump = Bump::new();
+    let mut map = RawMap::new_in(&bump);
+    for i in 0..100 {
+        let value = serde_json::value::to_raw_value(&format!("value {i}")).unwrap();
+        map.insert_copied("key", &value);
+    }
+    let value = serde_json::value::to_raw_value(&[1, 2]).unwrap();
+    map.insert_copied("other", &value);
+
+    let fresh = Bump::new();
+    let compacted = map.compact_into(&fresh);
+    drop(map);
+    assert!(fresh.allocated_bytes() < bump.allocated_bytes());
+    assert_eq!(
+        serde_json::to_string(&compacted).unwrap(),
+        r#"{"key":"value 99","other":[1,2]}"#
+    );
+    assert_eq!(compacted.get_index("other"), Some(1));
+}
+
+#[test]
+fn checkpoint() {
+    let s = r#"{"a":1,"b":2}"#;
+    let raw = serde_json::from_str(s).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let three = serde_json::from_str("3").unwrap();
+
+    let mut checkpoint = map.checkpoint();
+    checkpoint.insert("a", three);
+    checkpoint.insert("c", three);
+    checkpoint.push("b", three);
+    let value = serde_json::value::to_raw_value("copied").unwrap();
+    checkpoint.insert_copied("d", &value);
+    assert!(checkpoint.allocated_bytes() > 0);
+    assert_eq!(checkpoint.len(), 5);
+    assert_eq!(checkpoint.get("b").unwrap().get(), "3");
+    checkpoint.rollback();
+
+    assert_eq!(serde_json::to_string(&map).unwrap(), s);
+    assert_eq!(map.get_index("b"), Some(1));
+    assert!(map.get("c").is_none());
+    assert!(!map.has_duplicates());
+
+    // a key new since the checkpoint pushed twice
+    let mut checkpoint = map.checkpoint();
+    checkpoint.push("e", three);
+    checkpoint.push("e", three);
+    checkpoint.rollback();
+    assert!(map.get("e").is_none());
+    map.insert("e", three);
+    assert_eq!(map.get_index("e"), Some(2));
+    assert_eq!(map.swap_remove("e").unwrap().get(), "3");
+
+    let mut checkpoint = map.checkpoint();
+    checkpoint.insert("c", three);
+    checkpoint.commit();
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"a":1,"b":2,"c":3}"#
+    );
+}
+
+#[test]
+fn to_raw_value_in() {
+    let raw = serde_json::from_str(r#"{"a": 1, "nested": {"b": [1, 2]}}"#).unwrap();
+    let bump = Bump::new();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let mut nested = RawMap::from_raw_value(map.get("nested").unwrap(), &bump).unwrap();
+    nested.insert("c", serde_json::from_str("\"new\\nline\"").unwrap());
+
+    let mut rebuilt = RawMap::new_in(&bump);
+    rebuilt.insert("a", map.get("a").unwrap());
+    rebuilt.insert("nested", nested.to_raw_value_in(&bump));
+    let raw = rebuilt.to_raw_value_in(&bump);
+    assert_eq!(
+        raw.get(),
+        r#"{"a":1,"nested":{"b":[1, 2],"c":"new\nline"}}"#
+    );
+    assert_eq!(RawMap::new_in(&bump).to_raw_value_in(&bump).get(), "{}");
+}
+
+#[test]
+fn get_object() {
+    let raw = serde_json::from_str(r#"{"_geo": {"lat": 1, "lng": 2}, "title": "hello"}"#).unwrap();
+    let bump = Bump::new();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let mut map = crate::map::MemoRawMap::from_map(map);
+
+    let geo = map.get_object("_geo").unwrap().unwrap();
+    assert_eq!(geo.get("lat").unwrap().get(), "1");
+    let geo: *const _ = geo;
+    let allocated = bump.allocated_bytes();
+    let again = map.get_object("_geo").unwrap().unwrap();
+    assert!(std::ptr::eq(geo, again));
+    assert_eq!(bump.allocated_bytes(), allocated);
+
+    let previous = map.insert("_geo", serde_json::from_str(r#"{"lat": 3}"#).unwrap());
+    let geo = map.get_object("_geo").unwrap().unwrap();
+    assert_eq!(geo.get("lat").unwrap().get(), "3");
+
+    // the nested map of the replaced value was evicted, and is parsed again
+    map.insert("_geo", previous.unwrap());
+    let geo = map.get_object("_geo").unwrap().unwrap();
+    assert_eq!(geo.get("lng").unwrap().get(), "2");
+    assert!(map.swap_remove("_geo").is_some());
+    assert!(map.get_object("_geo").unwrap().is_none());
+
+    assert!(map.get_object("missing").unwrap().is_none());
+    let error = map.get_object("title").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected an object, found a string at `title`"
+    );
+}
+
+#[test]
+fn leaves() {
+    let raw = serde_json::from_str(
+        r#"{"id": 1, "doggo": {"name": "kefir", "toys": [{"kind": "ball"}, "bone", []], "empty": {}}, "tags": [null, true]}"#,
+    )
+    .unwrap();
+    let bump = Bump::new();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let leaves: Vec<_> = map
+        .leaves()
+        .map(|(path, value)| (path, value.get()))
+        .collect();
+    assert_eq!(
+        leaves,
+        [
+            ("id", "1"),
+            ("doggo.name", "\"kefir\""),
+            ("doggo.toys.kind", "\"ball\""),
+            ("doggo.toys", "\"bone\""),
+            ("tags", "null"),
+            ("tags", "true"),
+        ]
+    );
+
+    // the nested objects are not indexed, so their duplicated keys are all visited
+    let raw = serde_json::from_str(r#"{"a": {"b": 1, "b": 2}}"#).unwrap();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let leaves: Vec<_> = map
+        .leaves()
+        .map(|(path, value)| (path, value.get()))
+        .collect();
+    assert_eq!(leaves, [("a.b", "1"), ("a.b", "2")]);
+}
+
+#[test]
+fn validator() {
+    let raw =
+        serde_json::from_str(r#"{"title": "hello", "_private": 1, "body": "world"}"#).unwrap();
+    let bump = Bump::new();
+    let mut seen = Vec::new();
+    let error = RawMap::from_raw_value_with_validator(raw, &bump, |key, _value| {
+        seen.push(key.to_string());
+        if key.starts_with('_') {
+            Err("keys cannot start with an underscore")
+        } else {
+            Ok(())
+        }
+    })
+    .unwrap_err();
+    assert_eq!(seen, ["title", "_private"]);
+    assert_eq!(
+        error.to_string(),
+        "rejected entry `_private`: keys cannot start with an underscore"
+    );
+
+    let map = RawMap::from_raw_value_with_validator(raw, &bump, |_key, value| {
+        if value.get().len() > 10 {
+            Err(format!("value too large: {}", value.get().len()))
+        } else {
+            Ok(())
+        }
+    })
+    .unwrap();
+    assert_eq!(map.len(), 3);
+
+    let raw = serde_json::from_str("[]").unwrap();
+    let error =
+        RawMap::from_raw_value_with_validator(raw, &bump, |_, _| Ok::<_, String>(())).unwrap_err();
+    assert!(matches!(error, crate::Error::WrongKind { .. }));
+}
+
+#[test]
+fn with_capacity() {
+    let bump = Bump::new();
+    let value = serde_json::from_str("1").unwrap();
+    let mut map = RawMap::with_capacity_in(16, &bump);
+    let allocated = bump.allocated_bytes();
+    for key in [
+        "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m", "n",
+    ] {
+        map.insert(key, value);
+    }
+    assert_eq!(bump.allocated_bytes(), allocated);
+    assert_eq!(map.len(), 14);
+}
+
+#[test]
+fn raw_entry() {
+    use std::hash::BuildHasher as _;
+
+    use crate::map::RawEntryMut;
+
+    let raw = serde_json::from_str(r#"{"a": 1, "b": 2}"#).unwrap();
+    let bump = Bump::new();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let three = serde_json::from_str("3").unwrap();
+
+    let hash = map.hasher().hash_one("b");
+    assert_eq!(
+        map.raw_entry()
+            .from_key_hashed_nocheck(hash, "b")
+            .unwrap()
+            .1
+            .get(),
+        "2"
+    );
+    match map.raw_entry_mut().from_hash(hash, |key| key == "b") {
+        RawEntryMut::Occupied(mut entry) => {
+            assert_eq!(entry.index(), 1);
+            entry.insert(three);
+        }
+        RawEntryMut::Vacant(_) => panic!("`b` is present"),
+    }
+
+    let hash = map.hasher().hash_one("c");
+    assert!(map.raw_entry().from_hash(hash, |key| key == "c").is_none());
+    match map.raw_entry_mut().from_key_hashed_nocheck(hash, "c") {
+        RawEntryMut::Occupied(_) => panic!("`c` is absent"),
+        RawEntryMut::Vacant(entry) => {
+            entry.insert_hashed_nocheck(hash, "c", three);
+        }
+    }
+    assert_eq!(map.get_index("c"), Some(2));
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"a":1,"b":3,"c":3}"#
+    );
+}
+
+#[test]
+fn document_editor() {
+    use crate::map::DocumentEditor;
+
+    let raw = serde_json::from_str(
+        r#"{"id": 1, "kept": {"a" : [1, 2]}, "doggos": [{"name": "kefir", "age": 4}, {"name": "intel"}]}"#,
+    )
+    .unwrap();
+    let bump = Bump::new();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let kept = map.get("kept").unwrap();
+
+    let mut editor = DocumentEditor::new(&map);
+    // entering without modifying keeps the original value
+    assert!(editor.enter("kept").unwrap());
+    assert!(editor.leave());
+    assert!(editor.enter("doggos").unwrap());
+    assert!(editor.enter_index(1).unwrap());
+    editor
+        .set("age", serde_json::from_str("2").unwrap())
+        .unwrap();
+    assert!(editor.leave());
+    assert!(editor.enter_index(0).unwrap());
+    assert!(editor.remove("age").unwrap());
+    assert!(!editor.remove("age").unwrap());
+    let error = editor.append(kept).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected an array, found an object at `doggos[0]`"
+    );
+    let error = editor.enter("name").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected an object, found a string at `doggos[0].name`"
+    );
+    editor.leave_all();
+    assert!(!editor.enter("missing").unwrap());
+    editor.set("new", kept).unwrap();
+
+    let map = editor.commit();
+    assert!(std::ptr::eq(map.get("kept").unwrap(), kept));
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"id":1,"kept":{"a" : [1, 2]},"doggos":[{"name":"kefir"},{"name":"intel","age":2}],"new":{"a" : [1, 2]}}"#
+    );
+}
+
+#[test]
+fn subset() {
+    let bump = Bump::new();
+    let parse =
+        |s: &'static str| RawMap::from_raw_value(serde_json::from_str(s).unwrap(), &bump).unwrap();
+
+    let document = parse(
+        r#"{"id": 1, "title": "café", "doggo": {"name": "kefir", "age": 4}, "tags": [{"a": 1}, 2.0]}"#,
+    );
+    let update = parse(r#"{"title": "café", "doggo": {"age": 4.0}}"#);
+    assert!(update.is_subset_of(&document));
+    assert!(!document.is_subset_of(&update));
+    assert_eq!(
+        document.find_subset_mismatch(&update).as_deref(),
+        Some("id")
+    );
+
+    let update = parse(r#"{"doggo": {"age": 5}}"#);
+    assert_eq!(
+        update.find_subset_mismatch(&document).as_deref(),
+        Some("doggo.age")
+    );
+    let update = parse(r#"{"tags": [{"a": 1}, 2]}"#);
+    assert!(update.is_subset_of(&document));
+    let update = parse(r#"{"tags": [{}, 2]}"#);
+    assert_eq!(
+        update.find_subset_mismatch(&document).as_deref(),
+        Some("tags[0].a")
+    );
+    let update = parse(r#"{"tags": [{"a": 1}]}"#);
+    assert_eq!(
+        update.find_subset_mismatch(&document).as_deref(),
+        Some("tags[1]")
+    );
+    let update = parse(r#"{"id": "1"}"#);
+    assert_eq!(
+        update.find_subset_mismatch(&document).as_deref(),
+        Some("id")
+    );
+
+    // integers above 2^53 are compared exactly
+    let document = parse(r#"{"id": 9007199254740992, "negative": -9007199254740992}"#);
+    assert!(!parse(r#"{"id": 9007199254740993}"#).is_subset_of(&document));
+    assert!(parse(r#"{"id": 9007199254740992}"#).is_subset_of(&document));
+    assert!(!parse(r#"{"negative": -9007199254740993}"#).is_subset_of(&document));
+    assert!(parse(r#"{"negative": -9007199254740992}"#).is_subset_of(&document));
+
+    // integers and floats with the same value are equal, even out of the range of `f64`
+    let document = parse(r#"{"a": 1e400, "b": 2, "c": 18446744073709551616}"#);
+    assert!(document.is_subset_of(&document));
+    assert!(parse(r#"{"b": 2.0}"#).is_subset_of(&document));
+    assert!(parse(r#"{"c": 1.8446744073709551616e19}"#).is_subset_of(&document));
+    assert!(!parse(r#"{"c": 18446744073709551617}"#).is_subset_of(&document));
+    assert!(!parse(r#"{"a": -1e400}"#).is_subset_of(&document));
+}
+
+#[test]
+fn remaining_fields() {
+    use serde::de::DeserializeSeed as _;
+
+    use crate::de::RemainingFieldsSeed;
+
+    #[derive(serde::Deserialize)]
+    struct Header<'a> {
+        id: u32,
+        #[serde(rename = "type", borrow)]
+        kind: &'a str,
+    }
+
+    let bump = Bump::new();
+    let input = r#"{"id": 42, "payload": {"a": [1]}, "type": "update", "extra": null}"#;
+    let mut deserializer = serde_json::Deserializer::from_str(input);
+    let (header, remaining) = RemainingFieldsSeed::<Header>::new(&bump)
+        .deserialize(&mut deserializer)
+        .unwrap();
+    assert_eq!(header.id, 42);
+    assert_eq!(header.kind, "update");
+    assert_eq!(
+        serde_json::to_string(&remaining).unwrap(),
+        r#"{"payload":{"a": [1]},"extra":null}"#
+    );
+
+    let mut deserializer = serde_json::Deserializer::from_str(r#"{"id": "42", "type": "update"}"#);
+    assert!(RemainingFieldsSeed::<Header>::new(&bump)
+        .deserialize(&mut deserializer)
+        .is_err());
+}
+
+#[test]
+fn normalized_keys() {
+    use std::borrow::Cow;
+
+    use crate::map::{KeyNormalizer, Lowercase, NormalizedRawMap, Trim};
+
+    let raw = serde_json::from_str(r#"{"Title": 1, "body": 2, "TITLE": 3}"#).unwrap();
+    let bump = Bump::new();
+    let mut map = NormalizedRawMap::from_raw_value(raw, Lowercase, &bump).unwrap();
+    assert_eq!(map.len(), 2);
+    assert_eq!(map.get("tItLe").unwrap().get(), "3");
+    assert_eq!(map.get_index("BODY"), Some(1));
+    map.insert("Other", serde_json::from_str("4").unwrap());
+    assert_eq!(
+        serde_json::to_string(map.as_map()).unwrap(),
+        r#"{"title":3,"body":2,"other":4}"#
+    );
+
+    let mut map = NormalizedRawMap::new_in(Trim, &bump);
+    map.insert("  padded ", serde_json::from_str("1").unwrap());
+    assert_eq!(map[0].0, "padded");
+    assert!(map.get("padded  ").is_some());
+
+    fn underscores(key: &str) -> Cow<'_, str> {
+        Cow::Owned(key.replace('-', "_"))
+    }
+    let normalizer: fn(&str) -> Cow<'_, str> = underscores;
+    assert_eq!(normalizer.normalize("a-b"), "a_b");
+    let mut map = NormalizedRawMap::new_in(normalizer, &bump);
+    map.insert("a-b", serde_json::from_str("1").unwrap());
+    assert!(map.get("a_b").is_some());
+}
+
+#[cfg(feature = "relaxed")]
+#[test]
+fn relaxed() {
+    let bump = Bump::new();
+    let input = r#"{
+        // the identifier
+        "id": 1, /* inline */ "url": "http://example.com/*not a comment*/",
+        "tags": ["a", "b",],
+        "quote": "\",}",
+    }"#;
+    let map = RawMap::from_relaxed_str(input, &bump).unwrap();
+    assert_eq!(
+        map.get("url").unwrap().get(),
+        r#""http://example.com/*not a comment*/""#
+    );
+    assert_eq!(map.get("tags").unwrap().get(), r#"["a", "b"]"#);
+    assert_eq!(map.get("quote").unwrap().get(), r#""\",}""#);
+    assert_eq!(map.len(), 4);
+
+    let error = RawMap::from_relaxed_str(r#"{"a": 1 /* oops"#, &bump).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid JSON at byte offset 8: unterminated block comment"
+    );
+
+    // a comma not preceded by a value is not trailing
+    for input in [r#"{"a": [,]}"#, r#"{,}"#, r#"{"a": [1,,]}"#] {
+        assert!(RawMap::from_relaxed_str(input, &bump).is_err(), "{input}");
+    }
+}
+
+#[test]
+fn soa_map() {
+    use crate::SoaRawMap;
+
+    let raw = serde_json::from_str(r#"{"id": 1, "title": "hello", "id": 2}"#).unwrap();
+    let bump = Bump::new();
+    let mut map = SoaRawMap::from_raw_value(raw, &bump).unwrap();
+    assert_eq!(map.keys(), ["id", "title"]);
+    assert_eq!(map.get("id").unwrap().get(), "2");
+    assert_eq!(map.get_index("title"), Some(1));
+    assert!(map
+        .insert("body", serde_json::from_str("null").unwrap())
+        .is_none());
+    assert_eq!(map.values().len(), 3);
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"id":2,"title":"hello","body":null}"#
+    );
+
+    let frozen = crate::Freeze::freeze(&mut map);
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                assert_eq!(frozen.get("title").unwrap().get(), r#""hello""#);
+                assert_eq!(frozen.get_index("body"), Some(2));
+                assert!(frozen.get("missing").is_none());
+                assert_eq!(frozen.keys(), ["id", "title", "body"]);
+            })
+            .join()
+            .unwrap();
+    });
+    assert_eq!(
+        serde_json::to_string(&frozen).unwrap(),
+        r#"{"id":2,"title":"hello","body":null}"#
+    );
+
+    let raw = serde_json::from_str("[]").unwrap();
+    assert!(SoaRawMap::from_raw_value(raw, &bump).is_err());
+}
+
+#[test]
+fn columnar() {
+    use crate::columnar::{to_columns, ColumnType, ColumnValues};
+
+    let bump = Bump::new();
+    let mut maps: Vec<_> = [
+        r#"{"id": 1, "doggo": {"name": "kefir", "weight": 4.5}, "good": true}"#,
+        r#"{"id": 2, "doggo": {"name": "intel"}, "good": null}"#,
+        r#"{"id": 3}"#,
+    ]
+    .into_iter()
+    .map(|s| RawMap::from_raw_value(serde_json::from_str(s).unwrap(), &bump).unwrap())
+    .collect();
+    let frozen: Vec<_> = maps.iter_mut().map(|map| map.freeze()).collect();
+
+    let columns = to_columns(
+        &frozen,
+        &[
+            ("id", ColumnType::Int64),
+            ("doggo.name", ColumnType::LargeUtf8),
+            ("doggo.weight", ColumnType::Float64),
+            ("good", ColumnType::Boolean),
+        ],
+    )
+    .unwrap();
+    assert_eq!(columns[0].values, ColumnValues::Int64(vec![1, 2, 3]));
+    assert_eq!(
+        columns[1].values,
+        ColumnValues::LargeUtf8 {
+            offsets: vec![0, 5, 10, 10],
+            data: b"kefirintel".to_vec()
+        }
+    );
+    assert_eq!(
+        columns[1].validity.iter().collect::<Vec<_>>(),
+        [true, true, false]
+    );
+    assert_eq!(columns[1].validity.as_bytes(), [0b011]);
+    assert_eq!(columns[2].validity.as_bytes(), [0b001]);
+    assert_eq!(columns[3].validity.get(0), Some(true));
+    assert_eq!(columns[3].validity.get(1), Some(false));
+    assert_eq!(columns[3].validity.get(3), None);
+    let ColumnValues::Boolean(good) = &columns[3].values else {
+        panic!("not a boolean column");
+    };
+    assert_eq!(good.as_bytes(), [0b001]);
+
+    let error = to_columns(&frozen, &[("doggo.name", ColumnType::Int64)]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected a number, found a string at `[0].doggo.name`"
+    );
+}
+
+#[cfg(feature = "arrow")]
+#[test]
+fn arrow_arrays() {
+    use arrow_array::cast::AsArray as _;
+    use arrow_array::types::{Float64Type, Int64Type};
+    use arrow_array::Array as _;
+
+    use crate::columnar::{to_arrow_arrays, ColumnType};
+
+    let bump = Bump::new();
+    let mut maps: Vec<_> = [
+        r#"{"id": 1, "doggo": {"name": "kefir", "weight": 4.5}, "good": true}"#,
+        r#"{"id": 2, "doggo": {"name": "intel"}, "good": null}"#,
+        r#"{"id": 3}"#,
+    ]
+    .into_iter()
+    .map(|s| RawMap::from_raw_value(serde_json::from_str(s).unwrap(), &bump).unwrap())
+    .collect();
+    let frozen: Vec<_> = maps.iter_mut().map(|map| map.freeze()).collect();
+
+    let arrays = to_arrow_arrays(
+        &frozen,
+        &[
+            ("id", ColumnType::Int64),
+            ("doggo.name", ColumnType::LargeUtf8),
+            ("doggo.weight", ColumnType::Float64),
+            ("good", ColumnType::Boolean),
+        ],
+    )
+    .unwrap();
+    let ids = arrays[0].as_primitive::<Int64Type>();
+    assert_eq!(ids.values(), &[1, 2, 3]);
+    assert!(ids.nulls().is_none());
+    let names: Vec<_> = arrays[1].as_string::<i64>().iter().collect();
+    assert_eq!(names, [Some("kefir"), Some("intel"), None]);
+    let weights: Vec<_> = arrays[2].as_primitive::<Float64Type>().iter().collect();
+    assert_eq!(weights, [Some(4.5), None, None]);
+    let good: Vec<_> = arrays[3].as_boolean().iter().collect();
+    assert_eq!(good, [Some(true), None, None]);
+}
+
+#[test]
+fn spans() {
+    let s = r#"{"id": 1, "na\"me" :  "kefir" , "doggo": {"age": 3}}"#;
+    let bump = Bump::new();
+    let raw: &serde_json::value::RawValue = serde_json::from_str(s).unwrap();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let span = map.get_span("na\"me", raw.get()).unwrap();
+    assert_eq!(&s[span.key], r#""na\"me""#);
+    assert_eq!(&s[span.value], r#""kefir""#);
+    let span = map.get_span("doggo", raw.get()).unwrap();
+    assert_eq!(&s[span.value], r#"{"age": 3}"#);
+    assert!(map.get_span("missing", raw.get()).is_none());
+
+    map.insert("id", serde_json::from_str("2").unwrap());
+    assert!(map.get_span("id", raw.get()).is_none());
+
+    let s = r#"{"a": 1, "b": tru}"#;
+    let (_, errors) = RawMap::try_from_str_all_errors(s, &bump).unwrap();
+    assert_eq!(&s[errors[0].span.key.clone()], r#""b""#);
+    assert_eq!(&s[errors[0].span.value.clone()], "tru");
+}
+
+#[test]
+fn deserialize_at() {
+    let s = r#"{"id": 1, "doggos": [{"name": "kefir", "toys": ["ball"]}, {"name": "intel", "a/b": {"c~": 3}}]}"#;
+    let bump = Bump::new();
+    let raw: &serde_json::value::RawValue = serde_json::from_str(s).unwrap();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    assert_eq!(map.deserialize_at::<u32>("id").unwrap(), Some(1));
+    assert_eq!(
+        map.deserialize_at::<&str>("doggos[1].name").unwrap(),
+        Some("intel")
+    );
+    assert_eq!(
+        map.deserialize_at::<&str>("doggos[0].toys[0]").unwrap(),
+        Some("ball")
+    );
+    assert_eq!(
+        map.deserialize_at::<&str>("/doggos/0/name").unwrap(),
+        Some("kefir")
+    );
+    assert_eq!(
+        map.deserialize_at::<u32>("/doggos/1/a~1b/c~0").unwrap(),
+        Some(3)
+    );
+    assert_eq!(map.deserialize_at::<u32>("doggos[2].name").unwrap(), None);
+    assert_eq!(map.deserialize_at::<u32>("id.nested").unwrap(), None);
+    assert_eq!(map.deserialize_at::<u32>("missing").unwrap(), None);
+
+    let error = map.deserialize_at::<u32>("doggos[0].name").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "cannot deserialize the value at `doggos[0].name`: invalid type: string \"kefir\", expected u32 at line 1 column 17"
+    );
+}
+
+#[test]
+fn fields() {
+    let names = ["id", "name", "age"];
+    let bump = Bump::new();
+    let fields: [(u16, &[u8]); 4] = [(0, b"1"), (1, br#""kefir""#), (7, b"true"), (2, b"3")];
+    let map = RawMap::from_fields_in(fields, |id| names.get(id as usize).copied(), &bump).unwrap();
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"id":1,"name":"kefir","age":3}"#
+    );
+
+    let fields = map.to_fields(|key| names.iter().rposition(|name| *name == key).map(|id| 2 - id));
+    let ids: Vec<_> = fields.iter().map(|(id, _)| *id).collect();
+    assert_eq!(ids, [0, 1, 2]);
+    assert_eq!(fields[0].1, b"3");
+
+    let fields: [(u16, &[u8]); 1] = [(1, b"{")];
+    let error =
+        RawMap::from_fields_in(fields, |id| names.get(id as usize).copied(), &bump).unwrap_err();
+    assert!(error.to_string().contains("`name`"), "{error}");
+}
+
+#[cfg(feature = "obkv")]
+#[test]
+fn obkv_fields() {
+    let names = ["id", "name", "age"];
+    let resolve_id = |key: &str| {
+        names
+            .iter()
+            .position(|name| *name == key)
+            .map(|id| id as u16)
+    };
+    let bump = Bump::new();
+    let raw = serde_json::from_str(r#"{"name":"kefir","age":3,"id":1,"unknown":null}"#).unwrap();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let mut writer = obkv::KvWriter::memory();
+    map.write_obkv(&mut writer, resolve_id).unwrap();
+    let bytes = writer.into_inner().unwrap();
+    let reader = obkv::KvReader::<u16>::from_slice(&bytes);
+    assert_eq!(reader.get(1), Some(&br#""kefir""#[..]));
+
+    let decoded =
+        RawMap::from_obkv_in(reader, |id| names.get(id as usize).copied(), &bump).unwrap();
+    assert_eq!(
+        serde_json::to_string(&decoded).unwrap(),
+        r#"{"id":1,"name":"kefir","age":3}"#
+    );
+
+    let mut writer = obkv::KvWriter::memory();
+    assert!(map.write_obkv(&mut writer, |_| Some(0u16)).is_err());
+}
+
+#[test]
+fn dedup_batch() {
+    use crate::batch::{dedup_by_primary_key, Duplicate};
+
+    let bump = Bump::new();
+    let mut maps: Vec<_> = [
+        r#"{"doggo": {"id": 1}, "name": "kefir"}"#,
+        r#"{"doggo": {"id": "2"}, "name": "intel"}"#,
+        r#"{"doggo": {"id": "1"}, "name": "kefirounet"}"#,
+        r#"{"doggo": {"id": "a\u0062"}, "name": "escaped"}"#,
+        r#"{"doggo": {"id": "ab"}, "name": "plain"}"#,
+    ]
+    .iter()
+    .map(|s| RawMap::from_raw_value(serde_json::from_str(s).unwrap(), &bump).unwrap())
+    .collect();
+    let batch = dedup_by_primary_key(maps.iter_mut().map(|map| map.freeze()), "doggo.id").unwrap();
+    let names: Vec<_> = batch
+        .documents
+        .iter()
+        .map(|document| document.get("name").unwrap().get())
+        .collect();
+    assert_eq!(names, [r#""intel""#, r#""kefirounet""#, r#""plain""#]);
+    assert_eq!(
+        batch.duplicates,
+        [
+            Duplicate {
+                id: "1".to_owned(),
+                index: 0,
+                replaced_by: 2
+            },
+            Duplicate {
+                id: "ab".to_owned(),
+                index: 3,
+                replaced_by: 4
+            }
+        ]
+    );
+
+    let error = dedup_by_primary_key(maps.iter_mut().map(|map| map.freeze()), "id").unwrap_err();
+    assert_eq!(error.to_string(), "missing primary key `id` in document 0");
+
+    let mut map =
+        RawMap::from_raw_value(serde_json::from_str(r#"{"id": 1.5}"#).unwrap(), &bump).unwrap();
+    let error = dedup_by_primary_key([map.freeze()], "id").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "invalid primary key `id` in document 0: expected a string or an integer, found `1.5`"
+    );
+}
+
+#[test]
+fn preserving_round_trip() {
+    use crate::map::PreservingRawMap;
+
+    let bump = Bump::new();
+    let input = r#"{ "caf\u00e9": 1E2, "price": 1.50, "id": 1, "tag": "a\/b" }"#;
+    let mut map =
+        PreservingRawMap::from_raw_value(serde_json::from_str(input).unwrap(), &bump).unwrap();
+    assert_eq!(
+        map.non_canonical_numbers().collect::<Vec<_>>(),
+        ["café", "price"]
+    );
+    assert_eq!(
+        map.to_raw_value_in(&bump).get(),
+        r#"{"caf\u00e9":1E2,"price":1.50,"id":1,"tag":"a\/b"}"#
+    );
+
+    map.insert("café", serde_json::from_str("100").unwrap());
+    map.insert("new", serde_json::from_str("true").unwrap());
+    assert!(map.is_modified("café"));
+    assert!(!map.is_modified("price"));
+    assert_eq!(map.non_canonical_numbers().collect::<Vec<_>>(), ["price"]);
+    assert_eq!(
+        map.to_raw_value_in(&bump).get(),
+        r#"{"café":100,"price":1.50,"id":1,"tag":"a\/b","new":true}"#
+    );
+
+    assert!(crate::value::is_canonical_number(
+        serde_json::from_str::<&serde_json::value::RawValue>("-12.5").unwrap()
+    ));
+    assert!(!crate::value::is_canonical_number(
+        serde_json::from_str::<&serde_json::value::RawValue>("-0").unwrap()
+    ));
+}
+
+#[test]
+fn map_meta() {
+    let bump = Bump::new();
+    let map = RawMap::from_raw_value(
+        serde_json::from_str(r#"{"id": 1, "name": "kefir", "age": 3}"#).unwrap(),
+        &bump,
+    )
+    .unwrap();
+    let mut map = crate::map::RawMapMeta::<u16>::from_map(map);
+    assert_eq!(map.metas(), [0, 0, 0]);
+    *map.get_meta_mut("name").unwrap() = 1;
+
+    let bark = serde_json::from_str("true").unwrap();
+    assert!(map.insert("bark", bark, 7).is_none());
+    let (previous, meta) = map.insert("age", bark, 3).unwrap();
+    assert_eq!((previous.get(), meta), ("3", 0));
+
+    // the last entry takes the place of the removed one, with its metadata
+    let (value, meta) = map.swap_remove("id").unwrap();
+    assert_eq!((value.get(), meta), ("1", 0));
+    let keys: Vec<_> = map.iter().map(|(key, _, meta)| (key, *meta)).collect();
+    assert_eq!(keys, [("bark", 7), ("name", 1), ("age", 3)]);
+
+    map.retain(|key, _, meta| {
+        *meta += 1;
+        key != "name"
+    });
+    assert_eq!(map.get_meta("bark"), Some(&8));
+    assert_eq!(map.get_meta("age"), Some(&4));
+    assert_eq!(map.get_meta("name"), None);
+    assert_eq!(map.get_index("age"), Some(1));
+
+    // the first occurrence of a duplicated key is removed and the last one retained
+    let mut map = RawMap::new_in(&bump);
+    map.push("a", bark);
+    map.push("b", bark);
+    map.push("a", serde_json::from_str("false").unwrap());
+    let mut map = crate::map::RawMapMeta::<u16>::from_map(map);
+    map.metas_mut().copy_from_slice(&[1, 2, 3]);
+    map.retain(|_, _, meta| *meta != 1);
+    assert_eq!(map.metas(), [2, 3]);
+    assert_eq!(map.get("a").unwrap().get(), "false");
+    assert_eq!(map.get_meta("a"), Some(&3));
+    assert_eq!(map.get_index("b"), Some(0));
+}
+
+#[test]
+fn batch_key_frequencies() {
+    let bump = Bump::new();
+    let mut maps: Vec<_> = [
+        r#"{"id": 1, "name": "kefir", "age": 3}"#,
+        r#"{"id": 2, "name": "intel"}"#,
+        r#"{"id": 3}"#,
+    ]
+    .iter()
+    .map(|s| RawMap::from_raw_value(serde_json::from_str(s).unwrap(), &bump).unwrap())
+    .collect();
+    let frozen: Vec<_> = maps.iter_mut().map(|map| map.freeze()).collect();
+
+    let interner_bump = Bump::new();
+    let mut interner = crate::interner::Interner::new_in(&interner_bump);
+    let frequencies = crate::batch::key_frequencies(&frozen, &mut interner);
+    let mut frequencies: Vec<_> = frequencies.into_iter().collect();
+    frequencies.sort_unstable();
+    assert_eq!(frequencies, [("age", 1), ("id", 3), ("name", 2)]);
+    assert_eq!(interner.as_set().len(), 3);
+}
+
+#[test]
+fn float_arrays() {
+    use crate::value::{parse_f32_array_in, parse_f64_array_in};
+
+    let bump = Bump::new();
+    let raw = serde_json::from_str(" [ 0.5, -1.25e2 ,3, 1E-1 ] ").unwrap();
+    assert_eq!(
+        parse_f32_array_in(raw, &bump).unwrap(),
+        [0.5, -125.0, 3.0, 0.1]
+    );
+    assert_eq!(
+        parse_f64_array_in(raw, &bump).unwrap(),
+        [0.5, -125.0, 3.0, 0.1]
+    );
+    let raw = serde_json::from_str("[]").unwrap();
+    assert!(parse_f32_array_in(raw, &bump).unwrap().is_empty());
+
+    let raw = serde_json::from_str(r#"[1.0, "2.0"]"#).unwrap();
+    let error = parse_f32_array_in(raw, &bump).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected a number, found a string at `[1]`"
+    );
+    let raw = serde_json::from_str(r#"{"a": 1}"#).unwrap();
+    let error = parse_f64_array_in(raw, &bump).unwrap_err();
+    assert_eq!(error.to_string(), "expected an array, found an object");
+}
+
+#[test]
+fn map_values() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str(r#"{"id": 1, "password": "hunter2", "weight": 12}"#).unwrap();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let redacted: &serde_json::value::RawValue = serde_json::from_str(r#""***""#).unwrap();
+    let censored = map.map_values(|key, value| if key == "password" { redacted } else { value });
+    assert_eq!(
+        serde_json::to_string(&censored).unwrap(),
+        r#"{"id":1,"password":"***","weight":12}"#
+    );
+    assert_eq!(censored.get("id").unwrap().get(), "1");
+    assert!(std::ptr::eq(censored.as_slice()[2].0, map.as_slice()[2].0));
+
+    let grams = map
+        .try_map_values(|key, value| match key {
+            "weight" => {
+                let kilos: u64 = serde_json::from_str(value.get())?;
+                crate::value::to_raw_value_in(&(kilos * 1000), &bump)
+            }
+            _ => Ok(value),
+        })
+        .unwrap();
+    assert_eq!(grams.get("weight").unwrap().get(), "12000");
+
+    let error = map
+        .try_map_values(|_, value| serde_json::from_str::<u64>(value.get()).map(|_| value))
+        .unwrap_err();
+    assert!(
+        error.to_string().starts_with("rejected entry `password`"),
+        "{error}"
+    );
+}
+
+#[test]
+fn raw_vec_semantic_sort_dedup() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str(r#"["b", 2, "a", 1.0, {"x": 1}, "a", 1, {"x":1.0}, 10, "b"]"#)
+        .unwrap();
+    let mut vec = crate::RawVec::from_raw_value(raw, &bump).unwrap();
+    vec.sort_by_semantic_value();
+    vec.dedup_semantic();
+    assert_eq!(
+        serde_json::to_string(&vec).unwrap(),
+        r#"[1.0,2,10,"a","b",{"x": 1}]"#
+    );
+
+    // the numbers out of the range of `f64` are infinite floats
+    let raw = serde_json::from_str(r#"[1e400, 1e400, [1e400], [1E+400], -1e400]"#).unwrap();
+    let mut vec = crate::RawVec::from_raw_value(raw, &bump).unwrap();
+    vec.dedup_semantic();
+    assert_eq!(
+        serde_json::to_string(&vec).unwrap(),
+        r#"[1e400,[1e400],-1e400]"#
+    );
+}
+
+#[test]
+fn docids_accumulator() {
+    use crate::docids::DocidsAccumulator;
+    use bitpacking::BitPacker4x;
+
+    let mut bumps: Vec<_> = (0..3).map(|_| Bump::new()).collect();
+    let mut accumulator = DocidsAccumulator::<String, BitPacker4x>::new_in(&mut bumps);
+    std::thread::scope(|scope| {
+        for (thread, shard) in accumulator.shards_mut().iter_mut().enumerate() {
+            scope.spawn(move || {
+                for docid in 0..500 {
+                    shard.insert(format!("thread{thread}"), docid * 3 + thread as u32);
+                    shard.insert("shared".to_string(), docid);
+                }
+            });
+        }
+    });
+    assert!(accumulator
+        .shards_mut()
+        .iter()
+        .all(|shard| shard.len() == 2));
+
+    let frozen = accumulator.freeze();
+    assert_eq!(frozen.len(), 4);
+    let shared = frozen.get("shared").unwrap();
+    assert_eq!(shared.len(), 1500);
+    assert_eq!(shared.to_sorted_vec(), (0..500).collect::<Vec<_>>());
+    let thread1 = frozen.get("thread1").unwrap();
+    assert!(thread1.contains(301) && !thread1.contains(300));
+    assert_eq!(thread1.to_sorted_vec().len(), 500);
+    assert!(frozen.get("missing").is_none());
+}
+
+#[test]
+fn frozen_from_sorted_slice() {
+    use crate::map::FrozenRawMap;
+
+    let one = serde_json::from_str("1").unwrap();
+    let two = serde_json::from_str("2").unwrap();
+    let three = serde_json::from_str("3").unwrap();
+    let entries = [("a", one), ("b", two), ("c", three)];
+
+    let frozen = FrozenRawMap::from_sorted_slice(&entries).unwrap();
+    assert_eq!(frozen.get("b").unwrap().get(), "2");
+    assert_eq!(frozen.get_index("c"), Some(2));
+    assert_eq!(frozen.get_key_value("a").unwrap().1.get(), "1");
+    assert!(frozen.get("d").is_none());
+    assert_eq!(
+        serde_json::to_string(&frozen).unwrap(),
+        r#"{"a":1,"b":2,"c":3}"#
+    );
+
+    let unsorted = [("b", two), ("a", one)];
+    let error = FrozenRawMap::from_sorted_slice(&unsorted).unwrap_err();
+    assert_eq!(error.to_string(), "keys are not sorted at index 1");
+    let duplicated = [("a", one), ("a", two)];
+    let error = FrozenRawMap::from_sorted_slice(&duplicated).unwrap_err();
+    assert_eq!(error.to_string(), "duplicate key `a`");
+    let unchecked = FrozenRawMap::from_sorted_slice_unchecked(&entries);
+    assert_eq!(unchecked.get("c").unwrap().get(), "3");
+}
+
+#[test]
+fn swap_remove() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str(r#"{"a": 1, "b": 2, "c": 3, "d": 4}"#).unwrap();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    assert_eq!(map.swap_remove("b").unwrap().get(), "2");
+    assert_eq!(
+        serde_json::to_string(&map).unwrap(),
+        r#"{"a":1,"d":4,"c":3}"#
+    );
+    assert_eq!(map.get_index("d"), Some(1));
+    assert!(map.swap_remove("b").is_none());
+    assert_eq!(map.swap_remove("c").unwrap().get(), "3");
+    assert_eq!(map.get("d").unwrap().get(), "4");
+    assert_eq!(map.len(), 2);
+
+    let mut map = RawMap::new_in(&bump);
+    map.push("a", serde_json::from_str("1").unwrap());
+    map.push("b", serde_json::from_str("2").unwrap());
+    map.push("a", serde_json::from_str("3").unwrap());
+    assert_eq!(map.swap_remove("b").unwrap().get(), "2");
+    assert_eq!(map.get("a").unwrap().get(), "3");
+    assert_eq!(serde_json::to_string(&map).unwrap(), r#"{"a":1,"a":3}"#);
+}
+
+#[test]
+fn freeze_trait() {
+    use crate::Freeze;
+
+    fn frozen_len<T: Freeze>(state: T, len: impl FnOnce(&T::Frozen) -> usize + Send) -> usize {
+        let frozen = state.freeze();
+        std::thread::scope(|scope| scope.spawn(move || len(&frozen)).join().unwrap())
+    }
+
+    let bump = Bump::new();
+    let raw = serde_json::from_str("[1, 2, 3]").unwrap();
+    let mut vec = crate::RawVec::from_raw_value(raw, &bump).unwrap();
+    assert_eq!(frozen_len(&mut vec, |frozen| frozen.len()), 3);
+
+    let mut map = crate::RawBytesMap::new_in(&bump);
+    map.insert(b"key", raw);
+    assert_eq!(frozen_len(&mut map, |frozen| frozen.len()), 1);
+
+    let mut bumps = [Bump::new()];
+    let mut accumulator =
+        crate::docids::DocidsAccumulator::<&str, bitpacking::BitPacker4x>::new_in(&mut bumps);
+    accumulator.shards_mut()[0].insert("key", 1);
+    assert_eq!(frozen_len(accumulator, |frozen| frozen.len()), 1);
+}
+
+#[test]
+fn frozen_optimize() {
+    let keys: Vec<String> = (0..1000).map(|i| format!("key{i}")).collect();
+    let bump = Bump::new();
+    let mut map = RawMap::new_in(&bump);
+    let value = serde_json::from_str("1").unwrap();
+    for key in &keys {
+        map.insert(key, value);
+    }
+    map.push("key10", serde_json::from_str("2").unwrap());
+    let mut frozen = map.freeze();
+    let expected: Vec<_> = keys.iter().map(|key| frozen.get_index(key)).collect();
+
+    assert!(!frozen.is_optimized());
+    frozen.optimize();
+    assert!(frozen.is_optimized());
+    let indices: Vec<_> = keys.iter().map(|key| frozen.get_index(key)).collect();
+    assert_eq!(indices, expected);
+    assert_eq!(frozen.get_index("key10"), Some(1000));
+    assert_eq!(frozen.get("key10").unwrap().get(), "2");
+    assert!(frozen.get("key1000").is_none());
+    assert!(frozen.get("").is_none());
+}
+
+#[test]
+fn frozen_slice() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str(r#"{"a": 1, "b": 2, "c": 3, "d": 4, "e": 5}"#).unwrap();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let frozen = map.freeze();
+
+    let slice = frozen.slice(1..4);
+    assert_eq!(slice.len(), 3);
+    assert_eq!(slice.get("c").unwrap().get(), "3");
+    assert_eq!(slice.get_index("c"), Some(1));
+    assert!(slice.get("a").is_none() && slice.get("e").is_none());
+    let keys: Vec<_> = slice.iter().map(|(key, _)| *key).collect();
+    assert_eq!(keys, ["b", "c", "d"]);
+
+    let sub = slice.slice(1..3);
+    assert_eq!(sub.range(), 2..4);
+    assert_eq!(sub.get_key_value("d").unwrap().0, "d");
+    assert!(sub.get("b").is_none());
+
+    let total: usize = std::thread::scope(|scope| {
+        let handles: Vec<_> = [0..2, 2..5]
+            .into_iter()
+            .map(|range| {
+                let slice = frozen.slice(range);
+                scope.spawn(move || {
+                    slice
+                        .iter()
+                        .map(|(_, value)| value.get().len())
+                        .sum::<usize>()
+                })
+            })
+            .collect();
+        handles
+            .into_iter()
+            .map(|handle| handle.join().unwrap())
+            .sum()
+    });
+    assert_eq!(total, 5);
+}
+
+#[test]
+fn iter_in_order() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let keys: Vec<_> = map.iter_in_order(&[2, 0, 1]).map(|(key, _)| key).collect();
+    assert_eq!(keys, ["c", "a", "b"]);
+    let values: Vec<_> = map
+        .freeze()
+        .iter_in_order(&[1])
+        .map(|(_, value)| value.get())
+        .collect();
+    assert_eq!(values, ["2"]);
+    assert_eq!(map.iter_in_order(&[0, 0]).len(), 2);
+}
+
+#[test]
+#[should_panic = "index 3 out of bounds for a map of length 3"]
+fn iter_in_order_out_of_bounds() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str(r#"{"a": 1, "b": 2, "c": 3}"#).unwrap();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+    map.iter_in_order(&[0, 3]);
+}
+
+#[test]
+fn truncate_value() {
+    let bump = Bump::new();
+    let raw = serde_json::from_str(
+        r#"{"id": 1, "title": "héllo\nworld", "emoji": "a\ud83d\ude00b", "short": "ok"}"#,
+    )
+    .unwrap();
+    let mut map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    assert_eq!(map.value_len("title"), Some(15));
+    assert_eq!(map.value_len("missing"), None);
+
+    // `é` is two bytes long and `\n` is an escape sequence, neither is split
+    assert!(map.truncate_value("title", 9).unwrap());
+    assert_eq!(map.get("title").unwrap().get(), r#""héllo""#);
+    assert!(map.truncate_value("title", 4).unwrap());
+    assert_eq!(map.get("title").unwrap().get(), r#""h""#);
+    assert!(map.truncate_value("title", 1).unwrap());
+    assert_eq!(map.get("title").unwrap().get(), r#""""#);
+
+    // a surrogate pair is never split
+    assert!(map.truncate_value("emoji", 15).unwrap());
+    assert_eq!(map.get("emoji").unwrap().get(), r#""a\ud83d\ude00""#);
+    assert!(map.truncate_value("emoji", 14).unwrap());
+    assert_eq!(map.get("emoji").unwrap().get(), r#""a""#);
+
+    assert!(!map.truncate_value("short", 4).unwrap());
+    assert!(!map.truncate_value("missing", 4).unwrap());
+    let error = map.truncate_value("id", 0).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected a string, found a number at `id`"
+    );
+}
+
+#[test]
+fn check_types() {
+    use crate::value::Kind;
+
+    let bump = Bump::new();
+    let raw = serde_json::from_str(
+        r#"{"id": "1", "title": "kefir", "doggo": {"age": 3, "name": 12}, "tags": "cute"}"#,
+    )
+    .unwrap();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+
+    let schema = [
+        ("id", Kind::Number),
+        ("title", Kind::String),
+        ("doggo.age", Kind::Number),
+        ("doggo.name", Kind::String),
+        ("doggo.missing", Kind::Bool),
+        ("tags.first", Kind::String),
+        ("tags.second", Kind::String),
+        ("missing.nested", Kind::Object),
+    ];
+    let violations: Vec<_> = map
+        .check_types(&schema)
+        .iter()
+        .map(|error| error.to_string())
+        .collect();
+    assert_eq!(
+        violations,
+        [
+            "expected a string, found a number at `doggo.name`",
+            "expected a number, found a string at `id`",
+            "expected an object, found a string at `tags`",
+        ]
+    );
+
+    let mut map = map;
+    assert!(map.freeze().check_types(&schema[1..3]).is_empty());
+}
+
+#[test]
+fn get_str() {
+    let bump = Bump::new();
+    let raw =
+        serde_json::from_str(r#"{"plain": "kefir", "escaped": "café \"noir\"", "id": 1}"#).unwrap();
+    let map = RawMap::from_raw_value(raw, &bump).unwrap();
+    let mut map = crate::map::MemoRawMap::from_map(map);
+
+    let plain = map.get_str("plain").unwrap().unwrap();
+    assert_eq!(plain, "kefir");
+    assert!(map.get("plain").unwrap().get().contains(plain));
+
+    let escaped = map.get_str("escaped").unwrap().unwrap();
+    assert_eq!(escaped, "café \"noir\"");
+    assert!(std::ptr::eq(
+        escaped,
+        map.get_str("escaped").unwrap().unwrap()
+    ));
+
+    map.insert("escaped", serde_json::from_str(r#""\u0041""#).unwrap());
+    assert_eq!(map.get_str("escaped").unwrap(), Some("A"));
+    assert_eq!(map.get_str("missing").unwrap(), None);
+    let error = map.get_str("id").unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected a string, found a number at `id`"
+    );
+
+    map.unescape_strings();
+    let before = bump.allocated_bytes();
+    assert_eq!(map.get_str("escaped").unwrap(), Some("A"));
+    assert_eq!(bump.allocated_bytes(), before);
+}
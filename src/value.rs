@@ -1,3 +1,5 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
 use std::fmt;
 use std::hash::BuildHasher;
 
@@ -8,6 +10,8 @@ use serde::de::Visitor;
 
 use serde_json::value::RawValue;
 
+use crate::Error;
+
 /// Represents a partially parsed JSON value referencing the underlying data.
 pub enum Value<'bump, S = DefaultHashBuilder> {
     /// A JSON null value.
@@ -24,6 +28,50 @@ pub enum Value<'bump, S = DefaultHashBuilder> {
     Object(crate::RawMap<'bump, S>),
 }
 
+/// The kind of a JSON value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// A JSON null value.
+    Null,
+    /// A JSON boolean.
+    Bool,
+    /// A JSON number.
+    Number,
+    /// A JSON string.
+    String,
+    /// A JSON array.
+    Array,
+    /// A JSON object.
+    Object,
+}
+
+impl Kind {
+    /// Returns the kind of a raw value, by only looking at its first byte.
+    pub fn of(raw: &RawValue) -> Kind {
+        match raw.get().trim_start().as_bytes().first() {
+            Some(b'n') => Kind::Null,
+            Some(b't' | b'f') => Kind::Bool,
+            Some(b'"') => Kind::String,
+            Some(b'[') => Kind::Array,
+            Some(b'{') => Kind::Object,
+            _ => Kind::Number,
+        }
+    }
+}
+
+impl fmt::Display for Kind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Kind::Null => "null",
+            Kind::Bool => "a boolean",
+            Kind::Number => "a number",
+            Kind::String => "a string",
+            Kind::Array => "an array",
+            Kind::Object => "an object",
+        })
+    }
+}
+
 #[derive(Debug)]
 /// A JSON number
 pub enum Number {
@@ -41,14 +89,11 @@ impl<'de, 'bump: 'de> Value<'de> {
     /// The resulting value will refer to the underlying JSON data as much as possible.
     /// Any allocation that needs to occur (e.g., map nodes or escaped strings) will take place in the
     /// provided [`bumpalo::Bump`].
-    pub fn from_raw_value(
-        raw: &'de RawValue,
-        bump: &'bump Bump,
-    ) -> Result<Value<'de>, serde_json::Error> {
-        raw.deserialize_any(ValueVisitor {
+    pub fn from_raw_value(raw: &'de RawValue, bump: &'bump Bump) -> Result<Value<'de>, Error> {
+        Ok(raw.deserialize_any(ValueVisitor {
             bump,
             hash_builder: DefaultHashBuilder::default(),
-        })
+        })?)
     }
 }
 
@@ -62,8 +107,211 @@ impl<'de, 'bump: 'de, S: BuildHasher> Value<'de, S> {
         raw: &'de RawValue,
         hash_builder: S,
         bump: &'bump Bump,
-    ) -> Result<Value<'de, S>, serde_json::Error> {
-        raw.deserialize_any(ValueVisitor { bump, hash_builder })
+    ) -> Result<Value<'de, S>, Error> {
+        Ok(raw.deserialize_any(ValueVisitor { bump, hash_builder })?)
+    }
+}
+
+/// Returns `true` if `raw` is a number written the way serde_json serializes it.
+///
+/// Numbers in exponent notation, with trailing zeros in the fraction or a negative zero
+/// are not canonical: parsing and serializing them again would change their bytes.
+/// Returns `false` for values that are not numbers.
+pub fn is_canonical_number(raw: &RawValue) -> bool {
+    let json = raw.get();
+    serde_json::from_str::<serde_json::Number>(json).is_ok_and(|number| number.to_string() == json)
+}
+
+/// Copies a raw value into `bump`.
+pub fn copy_raw_value_in<'bump>(raw: &RawValue, bump: &'bump Bump) -> &'bump RawValue {
+    let json = bump.alloc_str(raw.get());
+    // a `RawValue` always contains valid JSON
+    serde_json::from_str(json).unwrap()
+}
+
+/// Serializes a value as compact JSON allocated in `bump`.
+///
+/// This is the bump-allocated equivalent of [`serde_json::value::to_raw_value`].
+///
+/// # Errors
+///
+/// - if the value cannot be serialized as JSON, for example a map with non-string keys.
+pub fn to_raw_value_in<'bump, T>(value: &T, bump: &'bump Bump) -> Result<&'bump RawValue, Error>
+where
+    T: serde::Serialize + ?Sized,
+{
+    let mut buffer = bumpalo::collections::Vec::new_in(bump);
+    serde_json::to_writer(&mut buffer, value)?;
+    buffer.shrink_to_fit();
+    // serde_json always generates valid UTF-8 JSON
+    let json = std::str::from_utf8(buffer.into_bump_slice()).unwrap();
+    Ok(serde_json::from_str(json).unwrap())
+}
+
+/// Parses a JSON array of numbers directly into a bump-allocated slice of `f32`s.
+///
+/// The numbers are read from the JSON text without going through serde for each element,
+/// which makes it suitable for large arrays such as embeddings.
+///
+/// # Errors
+///
+/// - if the raw value is not an array, or if one of its elements is not a number.
+///   The path of the error is the index of the element.
+pub fn parse_f32_array_in<'bump>(raw: &RawValue, bump: &'bump Bump) -> Result<&'bump [f32], Error> {
+    parse_float_array_in(raw, bump)
+}
+
+/// Parses a JSON array of numbers directly into a bump-allocated slice of `f64`s.
+///
+/// See [`parse_f32_array_in`].
+///
+/// # Errors
+///
+/// - if the raw value is not an array, or if one of its elements is not a number.
+///   The path of the error is the index of the element.
+pub fn parse_f64_array_in<'bump>(raw: &RawValue, bump: &'bump Bump) -> Result<&'bump [f64], Error> {
+    parse_float_array_in(raw, bump)
+}
+
+fn parse_float_array_in<'bump, F: std::str::FromStr>(
+    raw: &RawValue,
+    bump: &'bump Bump,
+) -> Result<&'bump [F], Error> {
+    let json = raw.get().trim();
+    let Some(elements) = json
+        .strip_prefix('[')
+        .and_then(|json| json.strip_suffix(']'))
+    else {
+        return Err(Error::WrongKind {
+            path: None,
+            expected: Kind::Array,
+            found: Kind::of(raw),
+        });
+    };
+    // a `RawValue` always contains valid JSON, so the elements are separated by the commas
+    // that are outside of strings, and a number never contains a comma
+    let mut output = bumpalo::collections::Vec::with_capacity_in(elements.len() / 8, bump);
+    let mut rest = elements.trim_start();
+    while !rest.is_empty() {
+        let index = output.len();
+        let end = rest
+            .bytes()
+            .position(|byte| !matches!(byte, b'0'..=b'9' | b'-' | b'+' | b'.' | b'e' | b'E'))
+            .unwrap_or(rest.len());
+        let number = match rest[..end].parse() {
+            Ok(number) if end > 0 => number,
+            _ => {
+                let element: &RawValue = serde_json::Deserializer::from_str(rest)
+                    .into_iter()
+                    .next()
+                    .and_then(Result::ok)
+                    .unwrap_or(raw);
+                return Err(Error::WrongKind {
+                    path: Some(format!("[{index}]")),
+                    expected: Kind::Number,
+                    found: Kind::of(element),
+                });
+            }
+        };
+        output.push(number);
+        rest = rest[end..].trim_start();
+        rest = rest.strip_prefix(',').unwrap_or(rest).trim_start();
+    }
+    Ok(output.into_bump_slice())
+}
+
+/// Compares two raw values as numbers or strings, parsing them lazily.
+///
+/// - numbers are compared numerically, the integers exactly, and ordered before strings;
+/// - strings are compared lexicographically after unescaping;
+/// - any other value is ordered after numbers and strings, by comparing the raw JSON text.
+///
+/// Meant to be used with [`crate::RawMap::sort_by_value`].
+pub fn cmp_number_or_string(left: &RawValue, right: &RawValue) -> Ordering {
+    match (ScalarKey::parse(left), ScalarKey::parse(right)) {
+        (ScalarKey::Number(left), ScalarKey::Number(right)) => left.cmp(right),
+        (ScalarKey::String(left), ScalarKey::String(right)) => left.cmp(&right),
+        (ScalarKey::Other(left), ScalarKey::Other(right)) => left.cmp(right),
+        (left, right) => left.rank().cmp(&right.rank()),
+    }
+}
+
+enum ScalarKey<'a> {
+    Number(NumberKey),
+    String(Cow<'a, str>),
+    Other(&'a str),
+}
+
+impl<'a> ScalarKey<'a> {
+    fn parse(raw: &'a RawValue) -> Self {
+        let s = raw.get();
+        match s.as_bytes().first() {
+            Some(b'-' | b'0'..=b'9') => match NumberKey::parse(s) {
+                Some(number) => ScalarKey::Number(number),
+                None => ScalarKey::Other(s),
+            },
+            Some(b'"') => match serde_json::from_str::<&str>(s) {
+                Ok(string) => ScalarKey::String(Cow::Borrowed(string)),
+                // the string contains escape sequences
+                Err(_) => match serde_json::from_str::<String>(s) {
+                    Ok(string) => ScalarKey::String(Cow::Owned(string)),
+                    Err(_) => ScalarKey::Other(s),
+                },
+            },
+            _ => ScalarKey::Other(s),
+        }
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            ScalarKey::Number(_) => 0,
+            ScalarKey::String(_) => 1,
+            ScalarKey::Other(_) => 2,
+        }
+    }
+}
+
+/// A JSON number, keeping the integers exact.
+#[derive(Clone, Copy)]
+enum NumberKey {
+    Integer(i128),
+    Float(f64),
+}
+
+impl NumberKey {
+    fn parse(s: &str) -> Option<Self> {
+        let is_integer = !s.bytes().any(|byte| matches!(byte, b'.' | b'e' | b'E'));
+        match s.parse() {
+            Ok(integer) if is_integer => Some(NumberKey::Integer(integer)),
+            // the integers that do not fit an `i128` and the numbers out of the range of `f64`
+            // are parsed as the closest float, possibly infinite
+            _ => s.parse().ok().map(NumberKey::Float),
+        }
+    }
+
+    /// A total order, where an integer and a float are equal only if they have the same exact value.
+    fn cmp(self, other: Self) -> Ordering {
+        match (self, other) {
+            (NumberKey::Integer(left), NumberKey::Integer(right)) => left.cmp(&right),
+            (NumberKey::Float(left), NumberKey::Float(right)) => cmp_floats(left, right),
+            (NumberKey::Integer(left), NumberKey::Float(right)) => cmp_integer_float(left, right),
+            (NumberKey::Float(left), NumberKey::Integer(right)) => {
+                cmp_integer_float(right, left).reverse()
+            }
+        }
+    }
+}
+
+fn cmp_floats(left: f64, right: f64) -> Ordering {
+    // JSON numbers are never NaN, and `-0.0` equals `0.0` as it does the integer `0`
+    left.partial_cmp(&right).unwrap_or(Ordering::Equal)
+}
+
+fn cmp_integer_float(integer: i128, float: f64) -> Ordering {
+    match cmp_floats(integer as f64, float) {
+        // the float is integral and within the range of `i128`, so the conversion is exact
+        Ordering::Equal if float.is_finite() => integer.cmp(&(float as i128)),
+        ordering => ordering,
     }
 }
 
@@ -4,6 +4,9 @@ use serde_json::value::RawValue;
 
 use bumpalo::collections::Vec as BVec;
 
+use crate::value::Kind;
+use crate::{Error, Freeze};
+
 mod de;
 /// Contains iterator types and implementations for [`RawVec`].
 pub mod iter;
@@ -28,6 +31,15 @@ impl Serialize for RawVec<'_> {
     }
 }
 
+impl<'a, 'bump> Freeze for &'a mut RawVec<'bump> {
+    type Frozen = &'a [&'bump RawValue];
+
+    #[inline]
+    fn freeze(self) -> Self::Frozen {
+        self.0.as_slice()
+    }
+}
+
 impl<'bump> RawVec<'bump> {
     /// Constructs a vector from a raw value and a bump allocator.
     ///
@@ -35,11 +47,9 @@ impl<'bump> RawVec<'bump> {
     ///
     /// - if the raw value cannot be parsed as a sequence (JSON array).
     #[inline]
-    pub fn from_raw_value(
-        raw: &'bump RawValue,
-        bump: &'bump Bump,
-    ) -> Result<Self, serde_json::Error> {
+    pub fn from_raw_value(raw: &'bump RawValue, bump: &'bump Bump) -> Result<Self, Error> {
         Self::from_deserializer(raw, bump)
+            .map_err(|error| Error::from_raw_value(raw, Kind::Array, error))
     }
 
     /// Constructs an empty vector backed by the specified bump allocator.
@@ -98,6 +108,31 @@ impl<'bump> RawVec<'bump> {
         self.0.into_bump_slice()
     }
 
+    /// Sorts the elements by their semantic value, with [`crate::value::cmp_number_or_string`].
+    ///
+    /// The sort is stable: elements with equal values keep their relative order.
+    #[inline]
+    pub fn sort_by_semantic_value(&mut self) {
+        self.0
+            .sort_by(|left, right| crate::value::cmp_number_or_string(left, right));
+    }
+
+    /// Removes the consecutive elements that are semantically equal to the element before them.
+    ///
+    /// Numbers are compared numerically, strings after unescaping, and objects and arrays element by element,
+    /// so that `1.0` and `1`, or `"\u0061"` and `"a"`, are duplicates.
+    /// Sort the vector with [`Self::sort_by_semantic_value`] beforehand to remove all the duplicate numbers
+    /// and strings. Objects and arrays are sorted by their raw JSON text, so equal ones written differently
+    /// may not end up next to each other.
+    pub fn dedup_semantic(&mut self) {
+        let mut scratch = Bump::new();
+        self.0.dedup_by(|right, left| {
+            let equal = crate::map::semantically_equal(left, right, &scratch);
+            scratch.reset();
+            equal
+        });
+    }
+
     /// Returns a shared reference to the allocator backing this `Vec`.
     #[inline]
     pub fn bump(&self) -> &'bump Bump {